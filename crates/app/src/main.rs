@@ -1,10 +1,143 @@
 #[macro_use]
 extern crate log;
 
-use clap::{arg, Command};
+use clap::parser::ValueSource;
+use clap::{arg, Arg, ArgAction, ArgGroup, ArgMatches, Command};
 use env_logger::{Builder, Target};
+use io::step_write::StepWriter;
 use log::LevelFilter;
 
+/*
+ * Writes a JUnit XML report of the run, if asked for with --junit
+ */
+fn write_junit(machine: &machine::Machine, matches: &ArgMatches) {
+    if let Some(path) = matches.get_one::<String>("junit") {
+        let test_cases = match matches
+            .get_one::<String>("junit-testcases")
+            .map(|s| s.as_str())
+        {
+            Some("requirement") => io::junit_write::TestCases::Requirement,
+            _ => io::junit_write::TestCases::Model,
+        };
+        if let Err(error) = io::junit_write::write(machine, test_cases, path) {
+            error!("{}", &error);
+            std::process::exit(exitcode::SOFTWARE);
+        }
+    }
+}
+
+/*
+ * The repeatable --model <FILE> <GENERATOR> argument
+ */
+fn model_arg() -> Arg {
+    Arg::new("model")
+        .long("model")
+        .short('m')
+        .num_args(2)
+        .value_names(["FILE", "GENERATOR"])
+        .action(ArgAction::Append)
+        .help("a file with model(s) to use, and the generator replacing the generators of its models. Can be given more than once")
+}
+
+/*
+ * Returns the files with models to use, each with the generator given for it, if any.
+ */
+fn inputs(matches: &ArgMatches) -> Vec<(String, Option<String>)> {
+    let mut inputs = Vec::new();
+    if let Some(input) = matches.get_one::<String>("INPUT") {
+        inputs.push((input.clone(), None));
+    }
+    if let Some(occurrences) = matches.get_occurrences::<String>("model") {
+        for mut values in occurrences {
+            if let (Some(file), Some(generator)) = (values.next(), values.next()) {
+                inputs.push((file.clone(), Some(generator.clone())));
+            }
+        }
+    }
+    inputs
+}
+
+/*
+ * Overrides the start position of the machine, if asked for with --start-element
+ */
+fn set_start(machine: &mut machine::Machine, matches: &ArgMatches) {
+    if let Some(id_or_name) = matches.get_one::<String>("start-element") {
+        let res = machine
+            .find_position(id_or_name)
+            .and_then(|position| machine.set_start(position));
+        if let Err(error) = res {
+            error!("{}", &error);
+            std::process::exit(exitcode::SOFTWARE);
+        }
+    }
+}
+
+/*
+ * Sets the probability of jumping through shared states, if asked for with --jump-probability
+ */
+fn set_jump_probability(machine: &mut machine::Machine, matches: &ArgMatches) {
+    if let Some(probability_str) = matches.get_one::<String>("jump-probability") {
+        let res = probability_str
+            .parse::<f32>()
+            .map_err(|err| {
+                format!(
+                    "The jump probability {:?} is not valid. {}",
+                    probability_str, err
+                )
+            })
+            .and_then(|probability| machine.set_jump_probability(probability));
+        if let Err(error) = res {
+            error!("{}", &error);
+            std::process::exit(exitcode::SOFTWARE);
+        }
+    }
+}
+
+/*
+ * Returns how the steps are written, as asked for with --schema, --verbose and --unvisited
+ */
+fn output_options(matches: &ArgMatches) -> io::step_write::JsonOptions {
+    let schema = match matches.get_one::<String>("schema").map(|s| s.as_str()) {
+        Some("graphwalker") => io::step_write::Schema::GraphWalker,
+        _ => io::step_write::Schema::Native,
+    };
+    io::step_write::JsonOptions {
+        schema,
+        verbose: matches.get_flag("verbose"),
+        unvisited: matches.get_flag("unvisited"),
+    }
+}
+
+/*
+ * Returns the format of the steps, as asked for with --output-format. The options of the
+ * json format can not be given together with another format.
+ */
+fn output_format(matches: &ArgMatches) -> Result<io::step_write::Format, String> {
+    let (name, format) = match matches
+        .get_one::<String>("output-format")
+        .map(|s| s.as_str())
+    {
+        Some("csv") => ("csv", io::step_write::Format::Csv),
+        Some("text") => ("text", io::step_write::Format::Text),
+        Some("gherkin") => ("gherkin", io::step_write::Format::Gherkin),
+        _ => return Ok(io::step_write::Format::Json(output_options(matches))),
+    };
+
+    let json_options: Vec<String> = ["schema", "verbose", "unvisited"]
+        .into_iter()
+        .filter(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        .map(|id| format!("--{}", id))
+        .collect();
+    if !json_options.is_empty() {
+        return Err(format!(
+            "The option(s) {} only apply to the json output format, not to {}",
+            json_options.join(", "),
+            name
+        ));
+    }
+    Ok(format)
+}
+
 fn main() {
     let matches = Command::new("graphwalker")
                           .version("0.0.1")
@@ -30,20 +163,103 @@ fn main() {
                                   )
                           )
                           .subcommand(
-                                Command::new("offline")
-                                        .about("Creates a path through the models. The output is written to standard outpout.")
+                                Command::new("requirements")
+                                        .about("Lists all unique requirements in the models. The output is written to standard outpout.")
+                                        .arg(arg!(<INPUT> "The file with model(s) to use"))
+                                    )
+                          .subcommand(
+                                Command::new("methods")
+                                        .about("Lists all unique names of the vertices and edges in the models. The output is written to standard outpout. Elements without a name are reported on standard error.")
+                                        .arg(arg!(<INPUT> "The file with model(s) to use"))
+                                        .arg(arg!(--json)
+                                            .help("writes the names as json, with the ids and models of the elements having the name, and the elements without a name")
+                                        )
+                                    )
+                          .subcommand(
+                                Command::new("source")
+                                        .about("Generates test-code stubs, with one method per unique vertex and edge name in the models, and a runner. The output is written to standard outpout.")
                                         .arg(arg!(<INPUT> "The file with model(s) to use"))
+                                        .arg(arg!(--lang <LANG>)
+                                            .help("select the language of the generated code")
+                                            .value_parser(["rust", "python", "java"])
+                                            .default_value("rust")
+                                        )
+                                    )
+                          .subcommand(
+                                Command::new("offline")
+                                        .about("Creates a path through the models. The output is written to standard outpout. The statistics of the run are written to standard error.")
+                                        .arg(arg!([INPUT] "The file with model(s) to use"))
+                                        .arg(model_arg())
+                                        .group(ArgGroup::new("models").args(["INPUT", "model"]).multiple(true).required(true))
+                                        .arg(Arg::new("start-element")
+                                            .long("start-element")
+                                            .value_name("ID|NAME")
+                                            .help("starts the walk at the vertex or edge with the id, or the unique name, ID|NAME, instead of at the start element of the models. Prefix it with the model id, as in MODEL_ID:ID, when models use the same ids")
+                                        )
                                         .arg(arg!(--seed <NUMBER>)
                                             .help("seeds the generator with NUMBER to get predictable outputs")
                                         )
+                                        .arg(arg!(--"jump-probability" <PROBABILITY>)
+                                            .help("the probability, between 0 and 1, of jumping through a shared state instead of taking an out edge, where both are possible")
+                                        )
+                                        .arg(arg!(--"output-format" <FORMAT>)
+                                            .help("select the format of the steps: json lines, comma separated values, a numbered list of element names, or a Gherkin scenario")
+                                            .value_parser(["json", "csv", "text", "gherkin"])
+                                            .default_value("json")
+                                        )
+                                        .arg(arg!(--schema <SCHEMA>)
+                                            .help("select the json schema of the steps, when the output format is json. The graphwalker schema is the one of the offline output of Java GraphWalker. Defaults to native")
+                                            .value_parser(["native", "graphwalker"])
+                                        )
+                                        .arg(arg!(-o --verbose)
+                                            .help("adds the id, the model name and the data of each step, in the graphwalker schema")
+                                        )
+                                        .arg(arg!(-u --unvisited)
+                                            .help("adds the elements of the model that are not yet visited to each step, when the output format is json")
+                                        )
+                                        .arg(arg!(--junit <PATH>)
+                                            .help("writes a JUnit XML report of the run to PATH")
+                                        )
+                                        .arg(arg!(--"junit-testcases" <TESTCASES>)
+                                            .help("select what a test case in the JUnit XML report represents")
+                                            .value_parser(["model", "requirement"])
+                                            .default_value("model")
+                                        )
+                                    )
+                          .subcommand(
+                                Command::new("replay")
+                                        .about("Replays a recorded path through the models. The output is written to standard outpout.")
+                                        .allow_missing_positional(true)
+                                        .arg(arg!([INPUT] "The file with model(s) to use"))
+                                        .arg(model_arg())
+                                        .group(ArgGroup::new("models").args(["INPUT", "model"]).multiple(true).required(true))
+                                        .arg(arg!(<PATH_FILE> "The file with the recorded path, as written by offline, or a saved profile"))
                                     )
                           .subcommand(
                                 Command::new("online")
                                         .about("Starts a REST service. The generated path is fetched through the REST API.")
-                                        .arg(arg!(<INPUT> "The file with model(s) to use"))
+                                        .arg(arg!([INPUT] "The file with model(s) to use"))
+                                        .arg(model_arg())
+                                        .group(ArgGroup::new("models").args(["INPUT", "model"]).multiple(true).required(true))
+                                        .arg(Arg::new("start-element")
+                                            .long("start-element")
+                                            .value_name("ID|NAME")
+                                            .help("starts the walk at the vertex or edge with the id, or the unique name, ID|NAME, instead of at the start element of the models. Prefix it with the model id, as in MODEL_ID:ID, when models use the same ids")
+                                        )
                                         .arg(arg!(--seed <NUMBER>)
                                             .help("seeds the generator with NUMBER to get predictable outputs")
                                         )
+                                        .arg(arg!(--"jump-probability" <PROBABILITY>)
+                                            .help("the probability, between 0 and 1, of jumping through a shared state instead of taking an out edge, where both are possible")
+                                        )
+                                        .arg(arg!(--junit <PATH>)
+                                            .help("writes a JUnit XML report of the run to PATH")
+                                        )
+                                        .arg(arg!(--"junit-testcases" <TESTCASES>)
+                                            .help("select what a test case in the JUnit XML report represents")
+                                            .value_parser(["model", "requirement"])
+                                            .default_value("model")
+                                        )
                                         .arg(arg!(--port <NUMBER>)
                                             .help("the port number of the REST service")
                                             .default_value("9090")
@@ -128,9 +344,32 @@ fn main() {
             }
         }
 
-        Some(("offline", offline_matches)) => {
+        Some(("requirements", requirements_matches)) => {
+            let file_read_result = io::read(
+                requirements_matches
+                    .get_one::<String>("INPUT")
+                    .expect("required"),
+            );
+            let models = match file_read_result {
+                Ok(models) => models,
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::SOFTWARE);
+                }
+            };
+
+            let mut requirements = std::collections::BTreeSet::new();
+            for model in models.models.values() {
+                requirements.extend(model.requirements());
+            }
+            for requirement in requirements {
+                println!("{}", requirement);
+            }
+        }
+
+        Some(("methods", methods_matches)) => {
             let file_read_result = io::read(
-                offline_matches
+                methods_matches
                     .get_one::<String>("INPUT")
                     .expect("required"),
             );
@@ -142,12 +381,66 @@ fn main() {
                 }
             };
 
+            let json = methods_matches.get_flag("json");
+            if !json {
+                for pos in io::methods_write::unnamed(&models) {
+                    eprintln!(
+                        "The element {} in model {} has no name",
+                        pos.element_id, pos.model_id
+                    );
+                }
+            }
+            if let Err(error) = io::methods_write::write(&models, json) {
+                error!("{}", &error);
+                std::process::exit(exitcode::SOFTWARE);
+            }
+        }
+
+        Some(("source", source_matches)) => {
+            let file_read_result =
+                io::read(source_matches.get_one::<String>("INPUT").expect("required"));
+            let models = match file_read_result {
+                Ok(models) => models,
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::SOFTWARE);
+                }
+            };
+
+            let lang = match source_matches.get_one::<String>("lang").map(|s| s.as_str()) {
+                Some("python") => io::source_write::Lang::Python,
+                Some("java") => io::source_write::Lang::Java,
+                _ => io::source_write::Lang::Rust,
+            };
+            io::source_write::write(models, lang);
+        }
+
+        Some(("offline", offline_matches)) => {
+            let format = match output_format(offline_matches) {
+                Ok(format) => format,
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::USAGE);
+                }
+            };
+
+            let file_read_result = io::read_all(&inputs(offline_matches));
+            let models = match file_read_result {
+                Ok(models) => models,
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::SOFTWARE);
+                }
+            };
+
             let mut machine = machine::Machine::new();
             let res = machine.load_models(models);
             if res.is_err() {
                 error!("{:?}", res.err());
                 std::process::exit(exitcode::SOFTWARE);
             }
+            set_start(&mut machine, offline_matches);
+            set_jump_probability(&mut machine, offline_matches);
 
             if let Some(number_str) = offline_matches.get_one::<String>("seed") {
                 match number_str.parse::<u64>() {
@@ -159,7 +452,16 @@ fn main() {
                 };
             }
 
-            match machine.walk() {
+            let res = io::step_write::walk(&mut machine, format);
+
+            match serde_json::to_string_pretty(&machine.statistics()) {
+                Ok(statistics) => eprintln!("{}", statistics),
+                Err(error) => error!("{}", &error),
+            }
+
+            write_junit(&machine, offline_matches);
+
+            match res {
                 Ok(()) => std::process::exit(exitcode::OK),
                 Err(error) => {
                     error!("{}", &error);
@@ -168,12 +470,51 @@ fn main() {
             }
         }
 
-        Some(("online", offline_matches)) => {
-            let file_read_result = io::read(
-                offline_matches
-                    .get_one::<String>("INPUT")
+        Some(("replay", replay_matches)) => {
+            let file_read_result = io::read_all(&inputs(replay_matches));
+            let models = match file_read_result {
+                Ok(models) => models,
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::SOFTWARE);
+                }
+            };
+
+            let path = match io::path_read::read(
+                replay_matches
+                    .get_one::<String>("PATH_FILE")
                     .expect("required"),
-            );
+            ) {
+                Ok(path) => path,
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::SOFTWARE);
+                }
+            };
+
+            let mut machine = machine::Machine::new();
+            let res = machine.load_models(models);
+            if res.is_err() {
+                error!("{:?}", res.err());
+                std::process::exit(exitcode::SOFTWARE);
+            }
+
+            let mut writer = io::step_write::JsonWriter::default();
+            let res = machine.replay(path, |machine, step| {
+                println!("{}", writer.step(machine, step)?);
+                Ok(())
+            });
+            match res {
+                Ok(()) => std::process::exit(exitcode::OK),
+                Err(error) => {
+                    error!("{}", &error);
+                    std::process::exit(exitcode::SOFTWARE);
+                }
+            }
+        }
+
+        Some(("online", offline_matches)) => {
+            let file_read_result = io::read_all(&inputs(offline_matches));
             let models = match file_read_result {
                 Ok(models) => models,
                 Err(error) => {
@@ -188,6 +529,8 @@ fn main() {
                 error!("{:?}", res.err());
                 std::process::exit(exitcode::SOFTWARE);
             }
+            set_start(&mut machine, offline_matches);
+            set_jump_probability(&mut machine, offline_matches);
 
             if let Some(number_str) = offline_matches.get_one::<String>("seed") {
                 match number_str.parse::<u64>() {
@@ -200,7 +543,10 @@ fn main() {
             }
 
             match machine.reset() {
-                Ok(()) => rest::run_rest_service(machine),
+                Ok(()) => {
+                    let machine = rest::run_rest_service(machine);
+                    write_junit(&machine, offline_matches);
+                }
                 Err(error) => {
                     error!("{}", &error);
                     std::process::exit(exitcode::SOFTWARE);
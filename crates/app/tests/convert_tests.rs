@@ -94,3 +94,17 @@ fn ouput_file_format_not_yet_implemented() -> Result<(), Box<dyn std::error::Err
 
     Ok(())
 }
+
+#[test]
+fn convert_unnamed_json_to_dot() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("convert").arg(resource_path("unnamed.json"));
+    cmd.arg("--format").arg("dot");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("digraph Unnamed"))
+        .stdout(predicate::str::contains("v2 [label=\"\\nid: v2\"]"));
+
+    Ok(())
+}
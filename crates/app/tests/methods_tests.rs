@@ -0,0 +1,89 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+fn resource_path(resource: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::new();
+    path.push(env!("CARGO_MANIFEST_DIR"));
+    path.push("..");
+    path.push("..");
+    path.push("resources");
+    path.push("models");
+    path.push(resource);
+    path
+}
+
+#[test]
+fn methods_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("methods");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided:",
+        ))
+        .stderr(predicate::str::contains("<INPUT>"));
+
+    Ok(())
+}
+
+#[test]
+fn methods() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("methods")
+        .arg(resource_path("simpleMultiModel.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("e1\ne2\n"))
+        .stderr(predicate::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn methods_unnamed() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("methods").arg(resource_path("unnamed.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("e_One\nv_One\n"))
+        .stderr(predicate::str::contains(
+            "The element e2 in model unnamed has no name",
+        ))
+        .stderr(predicate::str::contains(
+            "The element v2 in model unnamed has no name",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn methods_json() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("methods")
+        .arg(resource_path("unnamed.json"))
+        .arg("--json");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "methods": [
+                {"name": "e_One", "elements": [{"model_id": "unnamed", "element_id": "e1"}]},
+                {"name": "v_One", "elements": [{"model_id": "unnamed", "element_id": "v1"}]}
+            ],
+            "unnamed": [
+                {"model_id": "unnamed", "element_id": "e2"},
+                {"model_id": "unnamed", "element_id": "v2"}
+            ]
+        })
+    );
+
+    Ok(())
+}
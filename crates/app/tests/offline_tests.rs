@@ -23,7 +23,9 @@ fn offline_help() -> Result<(), Box<dyn std::error::Error>> {
         .stderr(predicate::str::contains(
             "the following required arguments were not provided:",
         ))
-        .stderr(predicate::str::contains("<INPUT>"))
+        .stderr(predicate::str::contains(
+            "<INPUT|--model <FILE> <GENERATOR>>",
+        ))
         .stderr(predicate::str::contains(
             "For more information, try '--help'",
         ));
@@ -55,3 +57,347 @@ fn offline() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn offline_statistics() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline").arg(resource_path("login.json"));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("\"total_steps\""))
+        .stderr(predicate::str::contains("\"status\": \"Ended\""));
+
+    Ok(())
+}
+
+#[test]
+fn offline_junit() -> Result<(), Box<dyn std::error::Error>> {
+    let mut path = std::env::temp_dir();
+    path.push("graphwalker_offline_junit.xml");
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline").arg(resource_path("login.json"));
+    cmd.arg("--junit").arg(&path);
+    cmd.assert().success();
+
+    let xml = std::fs::read_to_string(&path)?;
+    assert!(xml.contains("<testcase name=\"Login\" classname=\"login\""));
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn offline_models() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg("--model")
+        .arg(resource_path("login.json"))
+        .arg("random(length(3))")
+        .arg("--model")
+        .arg(resource_path("unnamed.json"))
+        .arg("random(length(0))");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(stdout.lines().count(), 3);
+    assert!(String::from_utf8(output.stderr)?.contains("\"unnamed\""));
+
+    Ok(())
+}
+
+#[test]
+fn offline_models_id_clash() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("simple.json"))
+        .arg("--model")
+        .arg(resource_path("simpleSingleModel.json"))
+        .arg("random(length(1))");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Model id: m1 is not unique"));
+
+    Ok(())
+}
+
+#[test]
+fn offline_models_invalid_generator() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg("--model")
+        .arg(resource_path("login.json"))
+        .arg("a_star(length(1))");
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "The generator of model id: login is not valid",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn offline_start_element() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--start-element")
+        .arg("v_Browse");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let first: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap())?;
+    assert_eq!(
+        first["position"],
+        serde_json::json!({"model_id": "login", "element_id": "n3"})
+    );
+
+    Ok(())
+}
+
+#[test]
+fn offline_start_element_not_found() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--start-element")
+        .arg("v_Unknown");
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "No element with the id or name \"v_Unknown\" was found",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn offline_jump_probability() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("petclinic.json"))
+        .arg("--seed")
+        .arg("1")
+        .arg("--jump-probability")
+        .arg("0.5");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"jump\":{\"shared_state\""))
+        .stderr(predicate::str::contains("\"shared_state_jumps\""));
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("petclinic.json"))
+        .arg("--jump-probability")
+        .arg("2");
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "The jump probability has to be between 0 and 1, got: 2",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn offline_seeded_output() -> Result<(), Box<dyn std::error::Error>> {
+    // The steps are written without timing, so the same seed writes the same output
+    let mut outputs = Vec::new();
+    for _ in 0..2 {
+        let mut cmd = Command::cargo_bin("graphwalker")?;
+        cmd.arg("offline")
+            .arg(resource_path("login.json"))
+            .arg("--seed")
+            .arg("1");
+        let output = cmd.assert().success().get_output().stdout.clone();
+        outputs.push(String::from_utf8(output)?);
+    }
+    assert!(!outputs[0].is_empty());
+    assert!(!outputs[0].contains("timestamp"));
+    assert!(!outputs[0].contains("duration_ms"));
+    assert_eq!(outputs[0], outputs[1]);
+
+    Ok(())
+}
+
+#[test]
+fn offline_start_element_model_qualified() -> Result<(), Box<dyn std::error::Error>> {
+    // The models start at the model-qualified start element m2:v1
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("simpleMultiModel.json"));
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        r#"{"model_name":"Model 2","element_name":"v1","#,
+    ));
+
+    // Both models have the element v1
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("simpleMultiModel.json"))
+        .arg("--start-element")
+        .arg("v1");
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "Qualify it with the model id, as in modelId:elementId",
+    ));
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("simpleMultiModel.json"))
+        .arg("--start-element")
+        .arg("m1:v1");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let first: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap())?;
+    assert_eq!(
+        first["position"],
+        serde_json::json!({"model_id": "m1", "element_id": "v1"})
+    );
+
+    Ok(())
+}
+
+#[test]
+fn offline_graphwalker_schema() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--schema")
+        .arg("graphwalker");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let first: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap())?;
+    assert_eq!(
+        first,
+        serde_json::json!({"currentElementName": "v_ClientNotRunning"})
+    );
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--schema")
+        .arg("graphwalker")
+        .arg("--verbose")
+        .arg("--unvisited");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let first: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap())?;
+    assert_eq!(first["modelName"], "Login");
+    assert_eq!(first["currentElementID"], "n1");
+    assert_eq!(first["currentElementName"], "v_ClientNotRunning");
+    assert_eq!(
+        first["data"],
+        serde_json::json!([{"rememberMe": "false"}, {"validLogin": "false"}])
+    );
+    assert_eq!(first["numberOfElements"], 11);
+    assert_eq!(first["numberOfUnvisitedElements"], 10);
+    assert_eq!(first["unvisitedElements"].as_array().unwrap().len(), 10);
+
+    let last: serde_json::Value = serde_json::from_str(stdout.lines().last().unwrap())?;
+    assert_eq!(last["numberOfUnvisitedElements"], 0);
+
+    Ok(())
+}
+
+#[test]
+fn offline_native_unvisited() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--unvisited");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let first: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap())?;
+    assert_eq!(
+        first["position"],
+        serde_json::json!({"model_id": "login", "element_id": "n1"})
+    );
+    assert_eq!(first["number_of_elements"], 11);
+    assert_eq!(first["number_of_unvisited_elements"], 10);
+    assert_eq!(
+        first["unvisited_elements"][0],
+        serde_json::json!({"element_id": "e1", "element_name": "e_StartClient"})
+    );
+
+    Ok(())
+}
+
+#[test]
+fn offline_output_format() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--output-format")
+        .arg("csv");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "step,model_id,model_name,element_id,element_name,rememberMe,validLogin\n1,login,Login,n1,v_ClientNotRunning,false,false\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--output-format")
+        .arg("text");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "1. v_ClientNotRunning\n2. e_StartClient\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--output-format")
+        .arg("gherkin");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Feature: Login\n\n  Scenario: A generated path\n    Given v_ClientNotRunning\n    When e_StartClient\n",
+        ))
+        .stderr(predicate::str::contains("\"status\": \"Ended\""));
+
+    Ok(())
+}
+
+#[test]
+fn offline_output_format_json_options() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--output-format")
+        .arg("csv")
+        .arg("--verbose")
+        .arg("--unvisited");
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "The option(s) --verbose, --unvisited only apply to the json output format, not to csv",
+    ));
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("login.json"))
+        .arg("--output-format")
+        .arg("text")
+        .arg("--schema")
+        .arg("native");
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "The option(s) --schema only apply to the json output format, not to text",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn offline_csv_global_data() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline")
+        .arg(resource_path("globalData.json"))
+        .arg("--output-format")
+        .arg("csv");
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "step,model_id,model_name,element_id,element_name,loggedIn,global.loggedIn\n1,login,Login,l1,v_LoggedOut,,false\n",
+    ));
+
+    Ok(())
+}
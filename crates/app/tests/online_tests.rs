@@ -23,7 +23,9 @@ fn online_help() -> Result<(), Box<dyn std::error::Error>> {
         .stderr(predicate::str::contains(
             "the following required arguments were not provided:",
         ))
-        .stderr(predicate::str::contains("<INPUT>"))
+        .stderr(predicate::str::contains(
+            "<INPUT|--model <FILE> <GENERATOR>>",
+        ))
         .stderr(predicate::str::contains(
             "For more information, try '--help'",
         ));
@@ -0,0 +1,144 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+fn resource_path(resource: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::new();
+    path.push(env!("CARGO_MANIFEST_DIR"));
+    path.push("..");
+    path.push("..");
+    path.push("resources");
+    path.push("models");
+    path.push(resource);
+    path
+}
+
+#[test]
+fn replay_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("replay").arg(resource_path("login.json"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided:",
+        ))
+        .stderr(predicate::str::contains("<PATH_FILE>"));
+
+    Ok(())
+}
+
+#[test]
+fn replay_offline_path() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline").arg(resource_path("login.json"));
+    cmd.arg("--seed").arg("147");
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let mut path = std::env::temp_dir();
+    path.push("graphwalker_replay_offline_path.json");
+    std::fs::write(&path, &output.stdout)?;
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("replay")
+        .arg(resource_path("login.json"))
+        .arg(&path);
+    let replayed = cmd.output()?;
+    assert!(replayed.status.success());
+
+    let positions = |out: &[u8]| -> Vec<String> {
+        String::from_utf8_lossy(out)
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["position"].to_string())
+            .collect()
+    };
+    assert_eq!(positions(&output.stdout), positions(&replayed.stdout));
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+/*
+ * Records a path with offline, using the arguments, and returns the file it is written to.
+ */
+fn record(name: &str, args: &[&str]) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("offline").args(args);
+    let output = cmd.output()?;
+    assert!(output.status.success());
+
+    let mut path = std::env::temp_dir();
+    path.push(name);
+    std::fs::write(&path, &output.stdout)?;
+    Ok(path)
+}
+
+#[test]
+fn replay_from_start_element() -> Result<(), Box<dyn std::error::Error>> {
+    let model = resource_path("login.json");
+    let model = model.to_str().expect("Expected a path");
+    let path = record(
+        "graphwalker_replay_from_start_element.json",
+        &[model, "--start-element", "v_Browse", "--seed", "147"],
+    )?;
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("replay").arg(model).arg(&path);
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        r#"{"model_name":"Login","element_name":"v_Browse""#,
+    ));
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn replay_models() -> Result<(), Box<dyn std::error::Error>> {
+    let model = resource_path("login.json");
+    let model = model.to_str().expect("Expected a path");
+    let generator = "random(length(20))";
+    let path = record(
+        "graphwalker_replay_models.json",
+        &["--model", model, generator, "--seed", "147"],
+    )?;
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("replay")
+        .arg("--model")
+        .arg(model)
+        .arg(generator)
+        .arg(&path);
+    let replayed = cmd.output()?;
+    assert!(replayed.status.success());
+    assert_eq!(
+        std::fs::read_to_string(&path)?,
+        String::from_utf8(replayed.stdout)?
+    );
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn replay_broken_path() -> Result<(), Box<dyn std::error::Error>> {
+    let mut path = std::env::temp_dir();
+    path.push("graphwalker_replay_broken_path.json");
+    std::fs::write(
+        &path,
+        r#"{"model_id":"login","element_id":"n1"}
+{"model_id":"login","element_id":"e2"}
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+    cmd.arg("replay")
+        .arg(resource_path("login.json"))
+        .arg(&path);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("can not be replayed"));
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
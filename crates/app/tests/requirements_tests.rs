@@ -0,0 +1,42 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+fn resource_path(resource: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::new();
+    path.push(env!("CARGO_MANIFEST_DIR"));
+    path.push("..");
+    path.push("..");
+    path.push("resources");
+    path.push("models");
+    path.push(resource);
+    path
+}
+
+#[test]
+fn requirements_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("requirements");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided:",
+        ))
+        .stderr(predicate::str::contains("<INPUT>"));
+
+    Ok(())
+}
+
+#[test]
+fn requirements() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("requirements")
+        .arg(resource_path("requirements.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("REQ-1\nREQ-2\nREQ-3\nREQ-4\n"));
+
+    Ok(())
+}
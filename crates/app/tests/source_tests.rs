@@ -0,0 +1,88 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+fn resource_path(resource: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::new();
+    path.push(env!("CARGO_MANIFEST_DIR"));
+    path.push("..");
+    path.push("..");
+    path.push("resources");
+    path.push("models");
+    path.push(resource);
+    path
+}
+
+#[test]
+fn source_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("source");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided:",
+        ))
+        .stderr(predicate::str::contains("<INPUT>"));
+
+    Ok(())
+}
+
+#[test]
+fn source_rust() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("source").arg(resource_path("login.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("pub trait Login {"))
+        .stdout(predicate::str::contains("fn v_browse("));
+
+    Ok(())
+}
+
+#[test]
+fn source_python() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("source")
+        .arg(resource_path("login.json"))
+        .arg("--lang")
+        .arg("python");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("class Login:"))
+        .stdout(predicate::str::contains("    def e_StartClient(self):"));
+
+    Ok(())
+}
+
+#[test]
+fn source_java() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("source")
+        .arg(resource_path("login.json"))
+        .arg("--lang")
+        .arg("java");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("public interface Login {"));
+
+    Ok(())
+}
+
+#[test]
+fn source_unsupported_lang() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("graphwalker")?;
+
+    cmd.arg("source")
+        .arg(resource_path("login.json"))
+        .arg("--lang")
+        .arg("cobol");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'cobol'"));
+
+    Ok(())
+}
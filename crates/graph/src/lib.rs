@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde_derive::{Deserialize, Serialize};
 
@@ -89,6 +89,20 @@ pub struct Models {
     pub models: BTreeMap<String, Model>,
 }
 
+impl Models {
+    /*
+     * Moves the models of `other` into these models. Fails, without changing anything,
+     * if a model id is used in both.
+     */
+    pub fn merge(&mut self, other: Models) -> Result<(), String> {
+        if let Some(key) = other.models.keys().find(|k| self.models.contains_key(*k)) {
+            return Err(format!("Model id: {} is not unique", key));
+        }
+        self.models.extend(other.models);
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Model {
@@ -105,6 +119,8 @@ pub struct Model {
     pub start_element_id: Option<String>,
     #[serde(default)]
     pub actions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub predefined_path_edge_ids: Vec<String>,
 }
 
 impl Model {
@@ -118,6 +134,7 @@ impl Model {
             generator: None,
             start_element_id: None,
             actions: vec![],
+            predefined_path_edge_ids: vec![],
         }
     }
 
@@ -139,6 +156,54 @@ impl Model {
         None
     }
 
+    pub fn get_requirements_for_id(&self, id: &String) -> Vec<String> {
+        if let Some(e) = self.edges.get(id) {
+            return e.requirements.clone();
+        }
+        if let Some(v) = self.vertices.get(id) {
+            return v.requirements.clone();
+        }
+        Vec::new()
+    }
+
+    /*
+     * Returns all unique requirements of the vertices and edges in the model.
+     */
+    pub fn requirements(&self) -> BTreeSet<String> {
+        let edges = self.edges.values().map(|e| &e.requirements);
+        let vertices = self.vertices.values().map(|v| &v.requirements);
+        edges.chain(vertices).flatten().cloned().collect()
+    }
+
+    /*
+     * Returns the ids of the vertices and edges in the model, grouped by their name.
+     * Elements without a name, or with an empty name, are left out.
+     */
+    pub fn element_ids_by_name(&self) -> BTreeMap<String, Vec<String>> {
+        let mut names: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let edges = self.edges.iter().map(|(id, e)| (id, &e.name));
+        let vertices = self.vertices.iter().map(|(id, v)| (id, &v.name));
+        for (id, name) in edges.chain(vertices) {
+            if let Some(name) = name.as_ref().filter(|name| !name.is_empty()) {
+                names.entry(name.clone()).or_default().push(id.clone());
+            }
+        }
+        names
+    }
+
+    /*
+     * Returns the ids of the vertices and edges in the model that have no name.
+     */
+    pub fn unnamed_elements(&self) -> Vec<String> {
+        let edges = self.edges.iter().map(|(id, e)| (id, &e.name));
+        let vertices = self.vertices.iter().map(|(id, v)| (id, &v.name));
+        edges
+            .chain(vertices)
+            .filter(|(_, name)| name.as_ref().is_none_or(|name| name.is_empty()))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
     pub fn out_edges(&mut self, id: String) -> Vec<Edge> {
         let mut out_edges: Vec<Edge> = Vec::new();
         for edge in self.edges.values() {
@@ -202,6 +267,10 @@ pub struct Edge {
 
     pub guard: Option<String>,
 
+    /// How strongly the transition depends on the system under test, 0-100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<u32>,
+
     pub source_vertex_id: Option<String>,
     pub target_vertex_id: Option<String>,
 }
@@ -215,6 +284,7 @@ impl Edge {
             source_vertex_id: None,
             target_vertex_id: None,
             guard: None,
+            dependency: None,
             requirements: vec![],
             actions: vec![],
         }
@@ -226,6 +296,12 @@ impl Edge {
         self
     }
 
+    #[must_use]
+    pub fn dependency(mut self, dependency: u32) -> Self {
+        self.dependency = Some(dependency);
+        self
+    }
+
     #[must_use]
     pub fn source_vertex_id(mut self, id: String) -> Self {
         self.source_vertex_id = Some(id);
@@ -312,6 +388,27 @@ mod tests {
         assert!(!model.has_id("x".to_string()));
     }
 
+    #[test]
+    fn element_names_test() {
+        let mut model = create_model();
+        assert!(model.element_ids_by_name().is_empty());
+        assert_eq!(
+            model.unnamed_elements(),
+            vec!["a->b", "b->c", "a", "b", "c"]
+        );
+
+        model.vertices.get_mut("a").unwrap().name = Some("v_A".to_string());
+        model.vertices.get_mut("b").unwrap().name = Some("v_A".to_string());
+        model.edges.get_mut("a->b").unwrap().name = Some("e_AB".to_string());
+        model.edges.get_mut("b->c").unwrap().name = Some(String::new());
+
+        let names = model.element_ids_by_name();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names["e_AB"], vec!["a->b"]);
+        assert_eq!(names["v_A"], vec!["a", "b"]);
+        assert_eq!(model.unnamed_elements(), vec!["b->c", "c"]);
+    }
+
     #[test]
     fn serialize_vertex() {
         let vertex = Vertex::new();
@@ -354,6 +451,71 @@ mod tests {
         assert!(v.requirements.is_empty());
     }
 
+    #[test]
+    fn deserialize_edge_dependency() {
+        let edge_json_str = r#"
+        {
+            "id": "e1",
+            "name": "e_StartClient",
+            "dependency": 80,
+            "sourceVertexId": "n1",
+            "targetVertexId": "n2"
+        }"#;
+        let e: Edge = serde_json::from_str(edge_json_str).expect("Test failed");
+        assert_eq!(e.dependency, Some(80));
+
+        let edge_json_str = serde_json::to_string(&e).unwrap();
+        assert!(edge_json_str.contains(r#""dependency":80"#));
+        let deserialized: Edge = serde_json::from_str(&edge_json_str).expect("Test failed");
+        assert_eq!(e, deserialized);
+
+        let e = Edge::new().id("e2".to_string());
+        assert!(e.dependency.is_none());
+        assert!(!serde_json::to_string(&e).unwrap().contains("dependency"));
+    }
+
+    #[test]
+    fn deserialize_model_predefined_path() {
+        let model_json_str = r#"
+        {
+            "id": "m1",
+            "vertices": [],
+            "edges": [],
+            "generator": "predefined_path(predefined_path)",
+            "predefinedPathEdgeIds": ["e1", "e2", "e1"]
+        }"#;
+        let m: Model = serde_json::from_str(model_json_str).expect("Test failed");
+        assert_eq!(m.predefined_path_edge_ids, vec!["e1", "e2", "e1"]);
+
+        let model_json_str = serde_json::to_string(&m).unwrap();
+        assert!(model_json_str.contains(r#""predefinedPathEdgeIds":["e1","e2","e1"]"#));
+        let deserialized: Model = serde_json::from_str(&model_json_str).expect("Test failed");
+        assert_eq!(m, deserialized);
+
+        assert!(!serde_json::to_string(&Model::new())
+            .unwrap()
+            .contains("predefinedPathEdgeIds"));
+    }
+
+    #[test]
+    fn merge_models() {
+        let mut models = Models {
+            models: BTreeMap::from([("m1".to_string(), Model::new())]),
+        };
+        let other = Models {
+            models: BTreeMap::from([("m2".to_string(), Model::new())]),
+        };
+        assert!(models.merge(other.clone()).is_ok());
+        assert_eq!(models.models.len(), 2);
+
+        // Negative test
+        assert_eq!(
+            models.merge(other),
+            Err("Model id: m2 is not unique".to_string())
+        );
+        assert_eq!(models.models.len(), 2);
+    }
+
     #[test]
     fn deserialize_models() {
         let vertex_json_str = r#"
@@ -3,17 +3,14 @@ use graph::Models;
 pub fn write(models: Models) {
     for i in models.models {
         let model = i.1;
-        println!(
-            "digraph {} {{",
-            model.name.clone().expect("Expected a model name")
-        );
+        println!("digraph {} {{", model.name.clone().unwrap_or(i.0.clone()));
 
         for n in &model.vertices {
             let v = n.1;
             println!(
                 "  {} [label=\"{}\\nid: {}\"]",
                 v.id.clone().expect("An id for the vertex."),
-                v.name.clone().expect("A name for the vertex."),
+                v.name.clone().unwrap_or_default(),
                 v.id.clone().expect("An id for the vertex.")
             );
         }
@@ -38,7 +35,7 @@ pub fn write(models: Models) {
                     .id
                     .clone()
                     .expect("Target vertex name"),
-                edge.name.clone().expect("Edge name"),
+                edge.name.clone().unwrap_or_default(),
                 edge.id.clone().expect("Edge id")
             );
             if edge.guard.is_some() {
@@ -0,0 +1,342 @@
+use machine::{statistics::Statistics, Failure, Machine, MachineStatus};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+
+/*
+ * Selects what a test case in the report represents.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TestCases {
+    Model,
+    Requirement,
+}
+
+struct TestCase {
+    name: String,
+    classname: String,
+    time: f64,
+    failure: Option<TestFailure>,
+    skipped: Option<String>,
+}
+
+struct TestFailure {
+    message: String,
+    details: String,
+}
+
+impl From<Failure> for TestFailure {
+    fn from(failure: Failure) -> Self {
+        let details = match failure.step {
+            Some(step) => format!(
+                "Failed at model: {}, element: {} ({})",
+                step.model_name, step.element_name, step.position.element_id
+            ),
+            None => "Failed before any step was taken".to_string(),
+        };
+        Self {
+            message: failure.reason,
+            details,
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/*
+ * One test case per model. A model fails if the failing step was in the model,
+ * if the machine failed before any step was taken, or if the stop conditions of
+ * the model were not fullfilled when the run was stopped.
+ */
+fn model_test_cases(machine: &Machine, statistics: &Statistics) -> Vec<TestCase> {
+    let mut test_cases = Vec::new();
+    for (key, ctx) in &machine.contexts {
+        let time_ms: f64 = machine
+            .profile
+            .steps
+            .iter()
+            .filter(|s| &s.position.model_id == key)
+            .filter_map(|s| s.duration_ms)
+            .sum();
+        let failure = machine.profile.failure.clone().filter(|f| match &f.step {
+            Some(step) => &step.position.model_id == key,
+            None => true,
+        });
+        let fullfilled = statistics.models.get(key).is_some_and(|m| m.fullfilled);
+        let failure = match failure {
+            Some(failure) => Some(TestFailure::from(failure)),
+            None if !fullfilled => Some(TestFailure {
+                message: "The stop conditions of the model were not fullfilled".to_string(),
+                details: format!("The machine status was {:?}", statistics.status),
+            }),
+            None if !matches!(
+                statistics.status,
+                MachineStatus::Ended | MachineStatus::Failed
+            ) =>
+            {
+                Some(TestFailure {
+                    message: "The run was stopped before it ended".to_string(),
+                    details: format!("The machine status was {:?}", statistics.status),
+                })
+            }
+            None => None,
+        };
+        test_cases.push(TestCase {
+            name: ctx.model().name.clone().unwrap_or(key.clone()),
+            classname: key.clone(),
+            time: time_ms / 1000f64,
+            failure,
+            skipped: None,
+        });
+    }
+    test_cases
+}
+
+/*
+ * One test case per requirement. A requirement fails if it is on the failing step,
+ * passes if it was visited, and is skipped if it was never reached.
+ */
+fn requirement_test_cases(machine: &Machine, statistics: &Statistics) -> Vec<TestCase> {
+    let mut requirements: BTreeSet<(String, String)> = BTreeSet::new();
+    for (key, ctx) in &machine.contexts {
+        let edges = ctx.model().edges.values().map(|e| &e.requirements);
+        let vertices = ctx.model().vertices.values().map(|v| &v.requirements);
+        for reqs in edges.chain(vertices) {
+            for req in reqs {
+                requirements.insert((key.clone(), req.clone()));
+            }
+        }
+    }
+
+    let mut test_cases = Vec::new();
+    for (key, req) in requirements {
+        let failure = machine.profile.failure.clone().filter(|f| match &f.step {
+            Some(step) => {
+                step.position.model_id == key
+                    && machine.contexts[&key]
+                        .model()
+                        .get_requirements_for_id(&step.position.element_id)
+                        .contains(&req)
+            }
+            None => true,
+        });
+        let failure = failure.map(TestFailure::from);
+        let skipped = match statistics.models.get(&key) {
+            Some(m) if failure.is_none() && m.unvisited_requirements.contains(&req) => {
+                Some("The requirement was never reached".to_string())
+            }
+            _ => None,
+        };
+        test_cases.push(TestCase {
+            name: req,
+            classname: key,
+            time: 0f64,
+            failure,
+            skipped,
+        });
+    }
+    test_cases
+}
+
+pub fn to_string(machine: &Machine, test_cases: TestCases) -> String {
+    let statistics = machine.statistics();
+    let test_cases = match test_cases {
+        TestCases::Model => model_test_cases(machine, &statistics),
+        TestCases::Requirement => requirement_test_cases(machine, &statistics),
+    };
+
+    let failures = test_cases.iter().filter(|t| t.failure.is_some()).count();
+    let skipped = test_cases.iter().filter(|t| t.skipped.is_some()).count();
+    let time = machine.profile.duration().as_secs_f64();
+
+    let mut properties = vec![
+        ("status".to_string(), format!("{:?}", statistics.status)),
+        (
+            "total_steps".to_string(),
+            statistics.total_steps.to_string(),
+        ),
+        (
+            "edge_coverage".to_string(),
+            statistics.edge_coverage.percent.to_string(),
+        ),
+        (
+            "vertex_coverage".to_string(),
+            statistics.vertex_coverage.percent.to_string(),
+        ),
+        (
+            "requirement_coverage".to_string(),
+            statistics.requirement_coverage.percent.to_string(),
+        ),
+    ];
+    for (key, m) in &statistics.models {
+        properties.push((
+            format!("{}.edge_coverage", key),
+            m.edge_coverage.percent.to_string(),
+        ));
+        properties.push((
+            format!("{}.vertex_coverage", key),
+            m.vertex_coverage.percent.to_string(),
+        ));
+        properties.push((
+            format!("{}.requirement_coverage", key),
+            m.requirement_coverage.percent.to_string(),
+        ));
+    }
+
+    let mut xml = String::new();
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="graphwalker" tests="{}" failures="{}" skipped="{}" time="{:.3}">"#,
+        test_cases.len(),
+        failures,
+        skipped,
+        time
+    );
+    let _ = writeln!(
+        xml,
+        r#"  <testsuite name="graphwalker" tests="{}" failures="{}" skipped="{}" time="{:.3}">"#,
+        test_cases.len(),
+        failures,
+        skipped,
+        time
+    );
+    let _ = writeln!(xml, "    <properties>");
+    for (name, value) in properties {
+        let _ = writeln!(
+            xml,
+            r#"      <property name="{}" value="{}"/>"#,
+            escape(&name),
+            escape(&value)
+        );
+    }
+    let _ = writeln!(xml, "    </properties>");
+
+    for test_case in test_cases {
+        let _ = write!(
+            xml,
+            r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+            escape(&test_case.name),
+            escape(&test_case.classname),
+            test_case.time
+        );
+        if let Some(failure) = test_case.failure {
+            let _ = writeln!(xml, ">");
+            let _ = writeln!(
+                xml,
+                r#"      <failure message="{}" type="Failed">{}</failure>"#,
+                escape(&failure.message),
+                escape(&failure.details)
+            );
+            let _ = writeln!(xml, "    </testcase>");
+        } else if let Some(message) = test_case.skipped {
+            let _ = writeln!(xml, ">");
+            let _ = writeln!(xml, r#"      <skipped message="{}"/>"#, escape(&message));
+            let _ = writeln!(xml, "    </testcase>");
+        } else {
+            let _ = writeln!(xml, "/>");
+        }
+    }
+
+    let _ = writeln!(xml, "  </testsuite>");
+    let _ = writeln!(xml, "</testsuites>");
+    xml
+}
+
+pub fn write(machine: &Machine, test_cases: TestCases, output_file: &str) -> Result<(), String> {
+    match fs::write(output_file, to_string(machine, test_cases)) {
+        Ok(()) => Ok(()),
+        Err(why) => {
+            log::error!("{:?}", why);
+            Err(why.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn resource_path(resource: &str) -> std::path::PathBuf {
+        let mut path = std::path::PathBuf::new();
+        path.push(env!("CARGO_MANIFEST_DIR"));
+        path.push("..");
+        path.push("..");
+        path.push("resources");
+        path.push("models");
+        path.push(resource);
+        path
+    }
+
+    fn login_machine() -> Machine {
+        let models = crate::json_read::read(resource_path("login.json").to_str().unwrap())
+            .expect("Expected the test file to be loaded");
+        let mut machine = Machine::new();
+        assert!(machine.load_models(models).is_ok());
+        machine
+    }
+
+    #[test]
+    fn passed_run() {
+        let mut machine = login_machine();
+        assert!(machine.walk().is_ok());
+
+        let xml = to_string(&machine, TestCases::Model);
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains(r#"<testsuite name="graphwalker" tests="1" failures="0" skipped="0""#));
+        assert!(xml.contains(r#"<property name="edge_coverage" value="100"/>"#));
+        assert!(xml.contains(r#"<property name="login.edge_coverage" value="100"/>"#));
+        assert!(xml.contains(r#"<testcase name="Login" classname="login""#));
+        assert!(!xml.contains("<failure"));
+    }
+
+    #[test]
+    fn failed_run() {
+        let mut machine = login_machine();
+        assert!(machine.reset().is_ok());
+        assert!(machine.step().is_ok());
+        assert!(machine.fail("Login <dialog> missing").is_ok());
+
+        let xml = to_string(&machine, TestCases::Model);
+        assert!(xml.contains(r#"tests="1" failures="1""#));
+        assert!(xml.contains(r#"<property name="status" value="Failed"/>"#));
+        assert!(xml.contains(
+            r#"<failure message="Login &lt;dialog&gt; missing" type="Failed">Failed at model: Login, element: v_ClientNotRunning (n1)</failure>"#
+        ));
+
+        // The login model has no requirements
+        let xml = to_string(&machine, TestCases::Requirement);
+        assert!(xml.contains(r#"tests="0" failures="0""#));
+    }
+
+    #[test]
+    fn stopped_run() {
+        let mut machine = login_machine();
+        assert!(machine.reset().is_ok());
+        assert!(machine.step().is_ok());
+        assert!(machine.step().is_ok());
+
+        let xml = to_string(&machine, TestCases::Model);
+        assert!(xml.contains(r#"tests="1" failures="1""#));
+        assert!(xml.contains(r#"<property name="status" value="Running"/>"#));
+        assert!(xml.contains(
+            r#"<failure message="The stop conditions of the model were not fullfilled" type="Failed">The machine status was Running</failure>"#
+        ));
+    }
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(
+            escape(r#"a<b && c>"d" 'e'"#),
+            "a&lt;b &amp;&amp; c&gt;&quot;d&quot; &apos;e&apos;"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use graph::Models;
 use log::{debug, trace};
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::path::Path;
 
@@ -11,8 +12,18 @@ pub mod dot_write;
 pub mod json_read;
 #[path = "json/write.rs"]
 pub mod json_write;
+#[path = "junit/write.rs"]
+pub mod junit_write;
+#[path = "methods/write.rs"]
+pub mod methods_write;
 #[path = "parsers/generator.rs"]
 pub mod parsers_generator;
+#[path = "path/read.rs"]
+pub mod path_read;
+#[path = "source/write.rs"]
+pub mod source_write;
+#[path = "step/write.rs"]
+pub mod step_write;
 
 fn get_extension_from_filename(file_name: &str) -> Option<&str> {
     Path::new(file_name).extension().and_then(OsStr::to_str)
@@ -40,3 +51,168 @@ pub fn read(input_file: &str) -> Result<Models, String> {
         Err("Could not open file".to_string())
     }
 }
+
+/*
+ * Reads the models of several files into one. A generator given together with a file
+ * replaces the generators of all models in that file.
+ * The walk starts at the start element of the first file that has one. Start elements of
+ * later files are dropped, and the kept one is qualified with its model id, as in
+ * modelId:elementId, when a later file uses the same element id.
+ */
+pub fn read_all(inputs: &[(String, Option<String>)]) -> Result<Models, String> {
+    let mut models = Models {
+        models: BTreeMap::new(),
+    };
+    let mut has_start = false;
+    let mut start: Option<(String, String)> = None;
+    let mut later_keys: Vec<String> = Vec::new();
+    for (input_file, generator) in inputs {
+        let mut file_models = read(input_file)?;
+        if let Some(generator) = generator {
+            for model in file_models.models.values_mut() {
+                model.generator = Some(generator.clone());
+            }
+        }
+        if has_start {
+            later_keys.extend(file_models.models.keys().cloned());
+            for (key, model) in file_models.models.iter_mut() {
+                if let Some(id) = model.start_element_id.take() {
+                    log::info!(
+                        "The start element id: {} of model id: {} in {} is ignored, the start element of an earlier file is kept",
+                        id, key, input_file
+                    );
+                }
+            }
+        } else {
+            has_start = file_models
+                .models
+                .values()
+                .any(|model| model.start_element_id.is_some());
+            start = start_of_models(&file_models);
+        }
+        if let Err(err) = models.merge(file_models) {
+            let msg = format!("Could not add the models in {}. {}", input_file, err);
+            log::error!("{}", msg);
+            return Err(msg);
+        }
+    }
+
+    if let Some((model_id, element_id)) = start {
+        let clashes = later_keys
+            .iter()
+            .any(|key| models.models[key].has_id(element_id.clone()));
+        if clashes {
+            let qualified = format!("{}:{}", model_id, element_id);
+            debug!("Qualifying the start element id: {}", qualified);
+            for model in models.models.values_mut() {
+                if model.start_element_id.is_some() {
+                    model.start_element_id = Some(qualified.clone());
+                }
+            }
+        }
+    }
+    Ok(models)
+}
+
+/*
+ * Returns the model id and element id of the start element of the models, if any model
+ * has one. An already model-qualified start element id is returned as it is.
+ */
+fn start_of_models(models: &Models) -> Option<(String, String)> {
+    let start_element_id = models
+        .models
+        .values()
+        .find_map(|model| model.start_element_id.clone())?;
+    if let Some((model_id, element_id)) = start_element_id.split_once(':') {
+        if models.models.contains_key(model_id) {
+            return Some((model_id.to_string(), element_id.to_string()));
+        }
+    }
+    models
+        .models
+        .iter()
+        .find(|(_, model)| model.has_id(start_element_id.clone()))
+        .map(|(key, _)| (key.clone(), start_element_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn resource_path(resource: &str) -> String {
+        let mut path = std::path::PathBuf::new();
+        path.push(env!("CARGO_MANIFEST_DIR"));
+        path.push("..");
+        path.push("..");
+        path.push("resources");
+        path.push("models");
+        path.push(resource);
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn read_all_files() {
+        let models = read_all(&[
+            (resource_path("login.json"), None),
+            (
+                resource_path("simple.json"),
+                Some("random(vertex_coverage(50))".to_string()),
+            ),
+        ])
+        .expect("Expected the models to be read");
+
+        assert_eq!(models.models.len(), 2);
+        assert_eq!(
+            models.models["login"].generator,
+            Some("random(edge_coverage(100))".to_string())
+        );
+        assert_eq!(
+            models.models["m1"].generator,
+            Some("random(vertex_coverage(50))".to_string())
+        );
+    }
+
+    #[test]
+    fn read_all_start_elements() {
+        // Only the start element of the first file is kept
+        let models = read_all(&[
+            (resource_path("login.json"), None),
+            (resource_path("unnamed.json"), None),
+        ])
+        .expect("Expected the models to be read");
+        assert_eq!(
+            models.models["login"].start_element_id,
+            Some("n1".to_string())
+        );
+        assert_eq!(models.models["unnamed"].start_element_id, None);
+
+        // ...and qualified, when a later file uses the same element id
+        let models = read_all(&[
+            (resource_path("unnamed.json"), None),
+            (resource_path("simple.json"), None),
+        ])
+        .expect("Expected the models to be read");
+        assert_eq!(
+            models.models["unnamed"].start_element_id,
+            Some("unnamed:v1".to_string())
+        );
+        assert_eq!(models.models["m1"].start_element_id, None);
+    }
+
+    #[test]
+    fn read_all_id_clash() {
+        let res = read_all(&[
+            (resource_path("simple.json"), None),
+            (resource_path("simpleSingleModel.json"), None),
+        ]);
+        assert_eq!(
+            res,
+            Err(format!(
+                "Could not add the models in {}. Model id: m1 is not unique",
+                resource_path("simpleSingleModel.json")
+            ))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use graph::Models;
+use machine::Position;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Method {
+    name: String,
+    elements: Vec<Position>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Methods {
+    methods: Vec<Method>,
+    unnamed: Vec<Position>,
+}
+
+/*
+ * Returns the positions of all vertices and edges in the models, grouped by their name.
+ */
+fn methods(models: &Models) -> BTreeMap<String, Vec<Position>> {
+    let mut methods: BTreeMap<String, Vec<Position>> = BTreeMap::new();
+    for (key, model) in &models.models {
+        for (name, ids) in model.element_ids_by_name() {
+            methods
+                .entry(name)
+                .or_default()
+                .extend(ids.into_iter().map(|id| Position {
+                    model_id: key.clone(),
+                    element_id: id,
+                }));
+        }
+    }
+    methods
+}
+
+/*
+ * Returns the positions of all vertices and edges in the models that have no name.
+ */
+pub fn unnamed(models: &Models) -> Vec<Position> {
+    let mut unnamed = Vec::new();
+    for (key, model) in &models.models {
+        for id in model.unnamed_elements() {
+            unnamed.push(Position {
+                model_id: key.clone(),
+                element_id: id,
+            });
+        }
+    }
+    unnamed
+}
+
+/*
+ * Lists the unique names of the vertices and edges, one per line. As json, each name comes
+ * with the elements having it, and the elements without a name are listed as well.
+ */
+pub fn to_string(models: &Models, json: bool) -> Result<String, String> {
+    if !json {
+        let mut names = String::new();
+        for name in methods(models).keys() {
+            names.push_str(name);
+            names.push('\n');
+        }
+        return Ok(names);
+    }
+
+    let methods = Methods {
+        methods: methods(models)
+            .into_iter()
+            .map(|(name, elements)| Method { name, elements })
+            .collect(),
+        unnamed: unnamed(models),
+    };
+    match serde_json::to_string_pretty(&methods) {
+        Ok(json_str) => Ok(json_str + "\n"),
+        Err(why) => {
+            log::error!("{:?}", why);
+            Err(why.to_string())
+        }
+    }
+}
+
+pub fn write(models: &Models, json: bool) -> Result<(), String> {
+    print!("{}", to_string(models, json)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn resource_path(resource: &str) -> std::path::PathBuf {
+        let mut path = std::path::PathBuf::new();
+        path.push(env!("CARGO_MANIFEST_DIR"));
+        path.push("..");
+        path.push("..");
+        path.push("resources");
+        path.push("models");
+        path.push(resource);
+        path
+    }
+
+    fn read(resource: &str) -> Models {
+        crate::json_read::read(resource_path(resource).to_str().unwrap())
+            .expect("Expected the test file to be loaded")
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            to_string(&read("login.json"), false),
+            Ok("e_Close\ne_Exit\ne_InvalidCredentials\ne_Logout\ne_StartClient\ne_ToggleRememberMe\ne_ValidPremiumCredentials\nv_Browse\nv_ClientNotRunning\nv_LoginPrompted\n".to_string())
+        );
+    }
+
+    #[test]
+    fn json() {
+        let json_str = to_string(&read("login.json"), true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(value["methods"].as_array().unwrap().len(), 10);
+        assert_eq!(value["methods"][4]["name"], "e_StartClient");
+        assert_eq!(
+            value["methods"][4]["elements"],
+            serde_json::json!([
+                {"model_id": "login", "element_id": "e1"},
+                {"model_id": "login", "element_id": "e7"}
+            ])
+        );
+        assert_eq!(value["unnamed"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn unnamed_elements() {
+        let models = read("unnamed.json");
+        assert_eq!(
+            unnamed(&models),
+            vec![
+                Position {
+                    model_id: "unnamed".to_string(),
+                    element_id: "e2".to_string(),
+                },
+                Position {
+                    model_id: "unnamed".to_string(),
+                    element_id: "v2".to_string(),
+                }
+            ]
+        );
+        assert_eq!(to_string(&models, false), Ok("e_One\nv_One\n".to_string()));
+    }
+}
@@ -0,0 +1,100 @@
+use log::{debug, trace};
+use machine::Position;
+use serde_json::Value;
+use std::fs;
+
+/*
+ * Returns the position of a step, or the position itself.
+ */
+fn position_of(value: &Value) -> Result<Position, String> {
+    let position = value.get("position").unwrap_or(value);
+    match serde_json::from_value::<Position>(position.clone()) {
+        Ok(position) => Ok(position),
+        Err(why) => Err(format!("Expected a step or a position: {}, {}", value, why)),
+    }
+}
+
+/*
+ * Parses a recorded path. The path is either the json lines written by the offline
+ * command, one step per line, or a saved Profile with a list of steps.
+ */
+pub fn parse(path_str: &str) -> Result<Vec<Position>, String> {
+    if let Ok(Value::Object(profile)) = serde_json::from_str::<Value>(path_str) {
+        if let Some(Value::Array(steps)) = profile.get("steps") {
+            return steps.iter().map(position_of).collect();
+        }
+    }
+
+    let mut positions = Vec::new();
+    for line in path_str.lines() {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            trace!("Skipping line: {}", line);
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(value) => positions.push(position_of(&value)?),
+            Err(why) => return Err(format!("Unable to parse line: {}, {}", line, why)),
+        }
+    }
+    Ok(positions)
+}
+
+pub fn read(input_file: &str) -> Result<Vec<Position>, String> {
+    debug!("{}", input_file);
+    match fs::read_to_string(input_file) {
+        Ok(path_str) => parse(&path_str),
+        Err(why) => {
+            log::error!("{:?}", why);
+            Err(why.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn position(model_id: &str, element_id: &str) -> Position {
+        Position {
+            model_id: model_id.to_string(),
+            element_id: element_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_json_lines() {
+        let path_str = r#"{"model_name":"Login","element_name":"v_ClientNotRunning","position":{"model_id":"login","element_id":"n1"},"data":[]}
+[ERROR] Not a step
+{"model_id":"login","element_id":"e1"}
+"#;
+        assert_eq!(
+            parse(path_str),
+            Ok(vec![position("login", "n1"), position("login", "e1")])
+        );
+    }
+
+    #[test]
+    fn parse_profile() {
+        let path_str = r#"
+        {
+            "steps": [
+                {"position": {"model_id": "login", "element_id": "n1"}},
+                {"position": {"model_id": "login", "element_id": "e1"}}
+            ],
+            "failure": null
+        }"#;
+        assert_eq!(
+            parse(path_str),
+            Ok(vec![position("login", "n1"), position("login", "e1")])
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse(r#"{"model_id":"login"}"#).is_err());
+        assert!(parse("{not json").is_err());
+    }
+}
@@ -0,0 +1,549 @@
+use graph::Models;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/*
+ * The language to generate the test-code stubs in.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lang {
+    Rust,
+    Python,
+    Java,
+}
+
+/*
+ * Returns the unique names of all vertices and edges in the models.
+ */
+fn element_names(models: &Models) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for model in models.models.values() {
+        names.extend(model.element_ids_by_name().into_keys());
+    }
+    names
+}
+
+/*
+ * Turns a name into an identifier, replacing all characters that are not allowed.
+ */
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous: Option<char> = None;
+    for c in identifier(name).chars() {
+        if c.is_ascii_uppercase() {
+            if previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+        previous = Some(c);
+    }
+    snake
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Keywords that can not be used as raw identifiers in Rust.
+const RUST_RESERVED: &[&str] = &["_", "crate", "self", "Self", "super"];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "case", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+    "import", "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass", "raise", "return",
+    "try", "type", "while", "with", "yield",
+];
+
+const JAVA_KEYWORDS: &[&str] = &[
+    "_",
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "record",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "var",
+    "void",
+    "volatile",
+    "while",
+    "yield",
+];
+
+/*
+ * Escapes an identifier that is a keyword of the language: with r# in Rust, and with
+ * a trailing underscore in Python and Java.
+ */
+fn escape_keyword(identifier: String, lang: Lang) -> String {
+    let keywords = match lang {
+        Lang::Rust if RUST_RESERVED.contains(&identifier.as_str()) => {
+            return format!("{}_", identifier)
+        }
+        Lang::Rust if RUST_KEYWORDS.contains(&identifier.as_str()) => {
+            return format!("r#{}", identifier)
+        }
+        Lang::Rust => return identifier,
+        Lang::Python => PYTHON_KEYWORDS,
+        Lang::Java => JAVA_KEYWORDS,
+    };
+    if keywords.contains(&identifier.as_str()) {
+        format!("{}_", identifier)
+    } else {
+        identifier
+    }
+}
+
+/*
+ * Returns the method name of each element name. Names that turn into the same identifier,
+ * like "Log in" and "log_in", are told apart with a numeric suffix.
+ */
+fn method_names(names: &BTreeSet<String>, lang: Lang) -> BTreeMap<String, String> {
+    let mut used = BTreeSet::new();
+    let mut method_names = BTreeMap::new();
+    for name in names {
+        let base = match lang {
+            Lang::Rust => snake_case(name),
+            Lang::Python | Lang::Java => identifier(name),
+        };
+        let mut method_name = escape_keyword(base.clone(), lang);
+        let mut suffix = 2;
+        while used.contains(&method_name) {
+            method_name = escape_keyword(format!("{}_{}", base, suffix), lang);
+            suffix += 1;
+        }
+        used.insert(method_name.clone());
+        method_names.insert(name.clone(), method_name);
+    }
+    method_names
+}
+
+/*
+ * Writes a Python string literal. Control characters are written as \x escapes.
+ */
+fn python_str(text: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(literal, "\\x{:02x}", c as u32);
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/*
+ * Writes a Java string literal. Characters outside of ASCII are written as \u escapes,
+ * and control characters as octal escapes, since javac translates \u escapes before
+ * the literal is read.
+ */
+fn java_str(text: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_ascii_control() => {
+                let _ = write!(literal, "\\{:03o}", c as u32);
+            }
+            c if c.is_ascii() => literal.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0u16; 2]) {
+                    let _ = write!(literal, "\\u{:04x}", unit);
+                }
+            }
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/*
+ * The name of the generated trait, interface or class, made from the names of the models.
+ */
+fn type_name(models: &Models, lang: Lang) -> String {
+    let mut type_name = String::new();
+    for (key, model) in &models.models {
+        let name = model.name.clone().unwrap_or(key.clone());
+        for part in identifier(&name).split('_') {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                type_name.push(first.to_ascii_uppercase());
+                type_name.push_str(chars.as_str());
+            }
+        }
+    }
+    if type_name.is_empty() || type_name.starts_with(|c: char| c.is_ascii_digit()) {
+        type_name.insert_str(0, "Model");
+    }
+    escape_keyword(type_name, lang)
+}
+
+fn rust(models: &Models) -> String {
+    let names = method_names(&element_names(models), Lang::Rust);
+    let type_name = type_name(models, Lang::Rust);
+
+    let mut source = String::new();
+    let _ = writeln!(source, "use machine::runner::{{HandlerContext, Runner}};");
+    let _ = writeln!(source, "use machine::Machine;");
+    let _ = writeln!(source, "use std::cell::RefCell;");
+    let _ = writeln!(source);
+    let _ = writeln!(source, "pub trait {} {{", type_name);
+    for method_name in names.values() {
+        let _ = writeln!(
+            source,
+            "    fn {}(&mut self, ctx: &mut HandlerContext) -> Result<(), String>;",
+            method_name
+        );
+    }
+    let _ = writeln!(source, "}}");
+    let _ = writeln!(source);
+    let _ = writeln!(
+        source,
+        "pub fn run<T: {}>(test: &mut T, machine: &mut Machine) -> Result<(), String> {{",
+        type_name
+    );
+    let _ = writeln!(source, "    let test = RefCell::new(test);");
+    let _ = writeln!(source, "    Runner::new(machine)");
+    for (name, method_name) in &names {
+        let _ = writeln!(
+            source,
+            "        .on({:?}, |ctx| test.borrow_mut().{}(ctx))",
+            name, method_name
+        );
+    }
+    let _ = writeln!(source, "        .run()");
+    let _ = writeln!(source, "}}");
+    source
+}
+
+fn python(models: &Models) -> String {
+    let names = method_names(&element_names(models), Lang::Python);
+    let type_name = type_name(models, Lang::Python);
+
+    let mut source = String::new();
+    let _ = writeln!(source, "import json");
+    let _ = writeln!(source, "import urllib.parse");
+    let _ = writeln!(source, "import urllib.request");
+    let _ = writeln!(source);
+    let _ = writeln!(source, "URL = \"http://127.0.0.1:3000\"");
+    let _ = writeln!(source);
+    let _ = writeln!(source);
+    let _ = writeln!(source, "class {}:", type_name);
+    for (name, method_name) in &names {
+        let _ = writeln!(source, "    def {}(self):", method_name);
+        let _ = writeln!(
+            source,
+            "        raise NotImplementedError({})",
+            python_str(name)
+        );
+        let _ = writeln!(source);
+    }
+    let _ = writeln!(source);
+    let _ = writeln!(source, "def request(method, path):");
+    let _ = writeln!(
+        source,
+        "    with urllib.request.urlopen(urllib.request.Request(URL + path, method=method)) as response:"
+    );
+    let _ = writeln!(source, "        body = response.read()");
+    let _ = writeln!(source, "        return json.loads(body) if body else None");
+    let _ = writeln!(source);
+    let _ = writeln!(source);
+    let _ = writeln!(source, "def run(test):");
+    let _ = writeln!(source, "    handlers = {{");
+    for (name, method_name) in &names {
+        let _ = writeln!(
+            source,
+            "        {}: test.{},",
+            python_str(name),
+            method_name
+        );
+    }
+    let _ = writeln!(source, "    }}");
+    let _ = writeln!(source, "    while request(\"GET\", \"/hasNext\"):");
+    let _ = writeln!(source, "        step = request(\"GET\", \"/getNext\")");
+    let _ = writeln!(
+        source,
+        "        handler = handlers.get(step[\"element_name\"])"
+    );
+    let _ = writeln!(source, "        if handler is None:");
+    let _ = writeln!(source, "            continue");
+    let _ = writeln!(source, "        try:");
+    let _ = writeln!(source, "            handler()");
+    let _ = writeln!(source, "        except Exception as e:");
+    let _ = writeln!(
+        source,
+        "            request(\"PUT\", \"/fail/\" + urllib.parse.quote(str(e), safe=\"\"))"
+    );
+    let _ = writeln!(source, "            raise");
+    let _ = writeln!(source);
+    let _ = writeln!(source);
+    let _ = writeln!(source, "if __name__ == \"__main__\":");
+    let _ = writeln!(source, "    run({}())", type_name);
+    source
+}
+
+fn java(models: &Models) -> String {
+    let names = method_names(&element_names(models), Lang::Java);
+    let type_name = type_name(models, Lang::Java);
+
+    let mut source = String::new();
+    let _ = writeln!(source, "import java.net.URI;");
+    let _ = writeln!(source, "import java.net.URLEncoder;");
+    let _ = writeln!(source, "import java.net.http.HttpClient;");
+    let _ = writeln!(source, "import java.net.http.HttpRequest;");
+    let _ = writeln!(source, "import java.net.http.HttpResponse;");
+    let _ = writeln!(source, "import java.nio.charset.StandardCharsets;");
+    let _ = writeln!(source, "import java.util.regex.Matcher;");
+    let _ = writeln!(source, "import java.util.regex.Pattern;");
+    let _ = writeln!(source);
+    let _ = writeln!(source, "public interface {} {{", type_name);
+    for method_name in names.values() {
+        let _ = writeln!(source, "    void {}() throws Exception;", method_name);
+    }
+    let _ = writeln!(source);
+    let _ = writeln!(
+        source,
+        "    static void run({} test) throws Exception {{",
+        type_name
+    );
+    let _ = writeln!(source, "        String url = \"http://127.0.0.1:3000\";");
+    let _ = writeln!(
+        source,
+        "        Pattern elementName = Pattern.compile(\"\\\"element_name\\\":\\\"([^\\\"]*)\\\"\");"
+    );
+    let _ = writeln!(
+        source,
+        "        HttpClient client = HttpClient.newHttpClient();"
+    );
+    let _ = writeln!(
+        source,
+        "        while (Boolean.parseBoolean(client.send(HttpRequest.newBuilder(URI.create(url + \"/hasNext\")).build(), HttpResponse.BodyHandlers.ofString()).body())) {{"
+    );
+    let _ = writeln!(
+        source,
+        "            String step = client.send(HttpRequest.newBuilder(URI.create(url + \"/getNext\")).build(), HttpResponse.BodyHandlers.ofString()).body();"
+    );
+    let _ = writeln!(
+        source,
+        "            Matcher matcher = elementName.matcher(step);"
+    );
+    let _ = writeln!(source, "            if (!matcher.find()) {{");
+    let _ = writeln!(source, "                continue;");
+    let _ = writeln!(source, "            }}");
+    let _ = writeln!(source, "            try {{");
+    let _ = writeln!(source, "                switch (matcher.group(1)) {{");
+    for (name, method_name) in &names {
+        let _ = writeln!(
+            source,
+            "                    case {}: test.{}(); break;",
+            java_str(name),
+            method_name
+        );
+    }
+    let _ = writeln!(source, "                    default: break;");
+    let _ = writeln!(source, "                }}");
+    let _ = writeln!(source, "            }} catch (Exception e) {{");
+    let _ = writeln!(
+        source,
+        "                String reason = URLEncoder.encode(String.valueOf(e.getMessage()), StandardCharsets.UTF_8).replace(\"+\", \"%20\");"
+    );
+    let _ = writeln!(
+        source,
+        "                client.send(HttpRequest.newBuilder(URI.create(url + \"/fail/\" + reason)).PUT(HttpRequest.BodyPublishers.noBody()).build(), HttpResponse.BodyHandlers.ofString());"
+    );
+    let _ = writeln!(source, "                throw e;");
+    let _ = writeln!(source, "            }}");
+    let _ = writeln!(source, "        }}");
+    let _ = writeln!(source, "    }}");
+    let _ = writeln!(source, "}}");
+    source
+}
+
+pub fn to_string(models: &Models, lang: Lang) -> String {
+    match lang {
+        Lang::Rust => rust(models),
+        Lang::Python => python(models),
+        Lang::Java => java(models),
+    }
+}
+
+pub fn write(models: Models, lang: Lang) {
+    print!("{}", to_string(&models, lang));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn resource_path(resource: &str) -> std::path::PathBuf {
+        let mut path = std::path::PathBuf::new();
+        path.push(env!("CARGO_MANIFEST_DIR"));
+        path.push("..");
+        path.push("..");
+        path.push("resources");
+        path.push("models");
+        path.push(resource);
+        path
+    }
+
+    fn login_models() -> Models {
+        crate::json_read::read(resource_path("login.json").to_str().unwrap())
+            .expect("Expected the test file to be loaded")
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(snake_case("e_StartClient"), "e_start_client");
+        assert_eq!(snake_case("v_Browse"), "v_browse");
+        assert_eq!(identifier("e Start-Client"), "e_Start_Client");
+        assert_eq!(identifier("1st"), "_1st");
+        assert_eq!(type_name(&login_models(), Lang::Rust), "Login");
+        assert_eq!(element_names(&login_models()).len(), 10);
+    }
+
+    #[test]
+    fn method_names_test() {
+        let names: BTreeSet<String> = ["Log in", "log_in", "log in", "type", "self", "class_"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        let rust = method_names(&names, Lang::Rust);
+        assert_eq!(rust["Log in"], "log_in");
+        assert_eq!(rust["log in"], "log_in_2");
+        assert_eq!(rust["log_in"], "log_in_3");
+        assert_eq!(rust["type"], "r#type");
+        assert_eq!(rust["self"], "self_");
+
+        let python = method_names(&names, Lang::Python);
+        assert_eq!(python["Log in"], "Log_in");
+        assert_eq!(python["log in"], "log_in");
+        assert_eq!(python["log_in"], "log_in_2");
+        assert_eq!(python["type"], "type_");
+        assert_eq!(python["class_"], "class_");
+
+        let java = method_names(&names, Lang::Java);
+        assert_eq!(java["self"], "self");
+        assert_eq!(java["type"], "type");
+        let names: BTreeSet<String> = ["class", "class_"].iter().map(|n| n.to_string()).collect();
+        let java = method_names(&names, Lang::Java);
+        assert_eq!(java["class"], "class_");
+        assert_eq!(java["class_"], "class__2");
+    }
+
+    #[test]
+    fn string_literals() {
+        assert_eq!(python_str(r#"a "b" \ c"#), r#""a \"b\" \\ c""#);
+        assert_eq!(python_str("\u{1}\n\u{e9}"), "\"\\x01\\n\u{e9}\"");
+        assert_eq!(java_str(r#"a "b" \ c"#), r#""a \"b\" \\ c""#);
+        assert_eq!(
+            java_str("\u{1}\n\u{e9}\u{1f600}"),
+            r#""\001\n\u00e9\ud83d\ude00""#
+        );
+    }
+
+    #[test]
+    fn rust_source() {
+        let source = to_string(&login_models(), Lang::Rust);
+        assert!(source.contains("pub trait Login {"));
+        assert!(source.contains(
+            "    fn e_start_client(&mut self, ctx: &mut HandlerContext) -> Result<(), String>;"
+        ));
+        assert!(source.contains(
+            r#"        .on("e_StartClient", |ctx| test.borrow_mut().e_start_client(ctx))"#
+        ));
+        // One method per unique name, e_StartClient is the name of two edges
+        assert_eq!(source.matches("fn e_start_client").count(), 1);
+    }
+
+    #[test]
+    fn python_source() {
+        let source = to_string(&login_models(), Lang::Python);
+        assert!(source.contains("class Login:"));
+        assert!(source.contains("    def v_Browse(self):"));
+        assert!(source.contains(r#"        "v_Browse": test.v_Browse,"#));
+    }
+
+    #[test]
+    fn java_source() {
+        let source = to_string(&login_models(), Lang::Java);
+        assert!(source.contains("public interface Login {"));
+        assert!(source.contains("    void e_Exit() throws Exception;"));
+        assert!(source.contains(r#"case "e_Exit": test.e_Exit(); break;"#));
+    }
+}
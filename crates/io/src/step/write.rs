@@ -0,0 +1,625 @@
+use machine::{Machine, Position, Step, GLOBAL_PREFIX};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/*
+ * The json schema of the steps written by a walk.
+ */
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub enum Schema {
+    /// The Step, as returned by the machine and the REST API.
+    #[default]
+    Native,
+    /// The schema of the offline output of Java GraphWalker.
+    GraphWalker,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct JsonOptions {
+    pub schema: Schema,
+    /// Adds the id, the model name and the data of the element, in the GraphWalker schema.
+    /// The native schema always has them.
+    pub verbose: bool,
+    /// Adds the elements of the current model that are not yet visited.
+    pub unvisited: bool,
+}
+
+/*
+ * The formats the steps of an offline walk can be written in.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json(JsonOptions),
+    Csv,
+    Text,
+    Gherkin,
+}
+
+/*
+ * Turns the steps of a walk into text, one step at a time.
+ */
+pub trait StepWriter {
+    /*
+     * Returns the lines written before the first step, if any.
+     */
+    fn header(&self) -> Option<String> {
+        None
+    }
+
+    /*
+     * Returns the step as one line, without the newline. The machine is the one walking,
+     * with the step just taken.
+     */
+    fn step(&mut self, machine: &Machine, step: &Step) -> Result<String, String>;
+}
+
+/*
+ * The elements of the model of a step that are not yet visited.
+ */
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Unvisited {
+    pub number_of_elements: usize,
+    pub number_of_unvisited_elements: usize,
+    pub unvisited_elements: Vec<UnvisitedElement>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct UnvisitedElement {
+    pub element_id: String,
+    pub element_name: String,
+}
+
+/*
+ * Returns the elements not yet visited in the model of the step.
+ */
+pub fn unvisited(machine: &Machine, step: &Step) -> Result<Unvisited, String> {
+    let ctx = match machine.contexts.get(&step.position.model_id) {
+        Some(ctx) => ctx,
+        None => {
+            let msg = format!("Could not find model id: {}", step.position.model_id);
+            log::error!("{}", msg);
+            return Err(msg);
+        }
+    };
+
+    let elements = ctx.elements();
+    let unvisited_elements: Vec<UnvisitedElement> = elements
+        .iter_visits()
+        .filter(|(_, visits)| *visits == 0)
+        .map(|(id, _)| UnvisitedElement {
+            element_id: id.clone(),
+            element_name: ctx.model().get_name_for_id(id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(Unvisited {
+        number_of_elements: elements.edge_count() + elements.vertex_count(),
+        number_of_unvisited_elements: unvisited_elements.len(),
+        unvisited_elements,
+    })
+}
+
+#[derive(Serialize)]
+struct NativeStep<'a> {
+    #[serde(flatten)]
+    step: &'a Step,
+    #[serde(flatten)]
+    unvisited: Option<Unvisited>,
+}
+
+/*
+ * A step in the schema of Java GraphWalker. The data is a list of objects with one
+ * variable each, and all values are strings, as in:
+ * {"modelName":"Login","currentElementID":"e1","currentElementName":"e_Init","data":[{"validLogin":"false"}]}
+ */
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphWalkerStep {
+    /// `modelName`, when verbose.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_name: Option<String>,
+    /// `currentElementID`, when verbose.
+    #[serde(rename = "currentElementID", skip_serializing_if = "Option::is_none")]
+    pub current_element_id: Option<String>,
+    /// `currentElementName`, always written.
+    pub current_element_name: String,
+    /// `data`, when verbose. Global variables are named with the `global.` prefix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<serde_json::Map<String, serde_json::Value>>>,
+    /// `numberOfElements`, when unvisited elements are asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_elements: Option<usize>,
+    /// `numberOfUnvisitedElements`, when unvisited elements are asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_unvisited_elements: Option<usize>,
+    /// `unvisitedElements`, when unvisited elements are asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unvisited_elements: Option<Vec<GraphWalkerElement>>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphWalkerElement {
+    pub element_name: String,
+    pub element_id: String,
+}
+
+impl GraphWalkerStep {
+    pub fn new(step: &Step, verbose: bool, unvisited: Option<Unvisited>) -> Self {
+        let mut gw_step = Self {
+            model_name: None,
+            current_element_id: None,
+            current_element_name: step.element_name.clone(),
+            data: None,
+            number_of_elements: None,
+            number_of_unvisited_elements: None,
+            unvisited_elements: None,
+        };
+
+        if verbose {
+            gw_step.model_name = Some(step.model_name.clone());
+            gw_step.current_element_id = Some(step.position.element_id.clone());
+
+            // The global variables keep their prefix, to tell them from the data of the model
+            let data = step
+                .data
+                .iter()
+                .map(|data| (data.name().to_string(), data.value()));
+            let globals = step
+                .global_data
+                .iter()
+                .map(|data| (format!("{}{}", GLOBAL_PREFIX, data.name()), data.value()));
+            gw_step.data = Some(
+                data.chain(globals)
+                    .map(|(name, value)| {
+                        let mut variable = serde_json::Map::new();
+                        variable.insert(name, serde_json::Value::String(value_str(value)));
+                        variable
+                    })
+                    .collect(),
+            );
+        }
+
+        if let Some(unvisited) = unvisited {
+            gw_step.number_of_elements = Some(unvisited.number_of_elements);
+            gw_step.number_of_unvisited_elements = Some(unvisited.number_of_unvisited_elements);
+            gw_step.unvisited_elements = Some(
+                unvisited
+                    .unvisited_elements
+                    .into_iter()
+                    .map(|element| GraphWalkerElement {
+                        element_name: element.element_name,
+                        element_id: element.element_id,
+                    })
+                    .collect(),
+            );
+        }
+        gw_step
+    }
+}
+
+/*
+ * Returns the value as GraphWalker writes it, where strings are not quoted.
+ */
+fn value_str(value: &evalexpr::Value) -> String {
+    match value {
+        evalexpr::Value::String(s) => s.clone(),
+        evalexpr::Value::Empty => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/*
+ * The steps as lines of json, in the schema of the options.
+ */
+#[derive(Default)]
+pub struct JsonWriter {
+    options: JsonOptions,
+}
+
+impl JsonWriter {
+    pub fn new(options: JsonOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl StepWriter for JsonWriter {
+    fn step(&mut self, machine: &Machine, step: &Step) -> Result<String, String> {
+        let unvisited = if self.options.unvisited {
+            Some(unvisited(machine, step)?)
+        } else {
+            None
+        };
+
+        let res = match self.options.schema {
+            Schema::Native => serde_json::to_string(&NativeStep {
+                step: &step.without_timing(),
+                unvisited,
+            }),
+            Schema::GraphWalker => {
+                serde_json::to_string(&GraphWalkerStep::new(step, self.options.verbose, unvisited))
+            }
+        };
+        res.map_err(|err| {
+            let msg = format!("Could extract the json str from step: {:?}", err);
+            log::warn!("{}", msg);
+            msg
+        })
+    }
+}
+
+/*
+ * Returns the name of the element of the step, or its id when it has no name.
+ */
+fn element_name(step: &Step) -> &str {
+    if step.element_name.is_empty() {
+        &step.position.element_id
+    } else {
+        &step.element_name
+    }
+}
+
+/*
+ * The steps as comma separated values. Each variable assigned by the actions of the models
+ * has a column of its own, after the columns of the step. Global variables follow the
+ * variables of the models, named with the `global.` prefix.
+ */
+pub struct CsvWriter {
+    count: usize,
+    variables: Vec<String>,
+}
+
+impl CsvWriter {
+    pub fn new(machine: &Machine) -> Self {
+        let mut names = BTreeSet::new();
+        for ctx in machine.contexts.values() {
+            let model = ctx.model();
+            let actions = model
+                .actions
+                .iter()
+                .chain(model.edges.values().flat_map(|e| &e.actions))
+                .chain(model.vertices.values().flat_map(|v| &v.actions));
+            for action in actions {
+                // Actions that do not parse fail the walk, and have no columns
+                if let Ok(tree) = evalexpr::build_operator_tree(action) {
+                    names.extend(tree.iter_write_variable_identifiers().map(String::from));
+                }
+            }
+        }
+        let (globals, mut variables): (Vec<String>, Vec<String>) = names
+            .into_iter()
+            .partition(|name| name.starts_with(GLOBAL_PREFIX));
+        variables.extend(globals);
+        Self {
+            count: 0,
+            variables,
+        }
+    }
+
+    /*
+     * Returns the value of the variable in the step, or an empty string if it is not set.
+     */
+    fn value(&self, step: &Step, variable: &str) -> String {
+        let data = match variable.strip_prefix(GLOBAL_PREFIX) {
+            Some(name) => step.global_data.iter().find(|data| data.name() == name),
+            None => step.data.iter().find(|data| data.name() == variable),
+        };
+        data.map(|data| value_str(data.value())).unwrap_or_default()
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl StepWriter for CsvWriter {
+    fn header(&self) -> Option<String> {
+        let columns = [
+            "step",
+            "model_id",
+            "model_name",
+            "element_id",
+            "element_name",
+        ];
+        Some(
+            columns
+                .iter()
+                .map(|column| column.to_string())
+                .chain(self.variables.iter().map(|variable| csv_field(variable)))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    }
+
+    fn step(&mut self, _machine: &Machine, step: &Step) -> Result<String, String> {
+        self.count += 1;
+        let fields = [
+            self.count.to_string(),
+            step.position.model_id.clone(),
+            step.model_name.clone(),
+            step.position.element_id.clone(),
+            step.element_name.clone(),
+        ];
+        Ok(fields
+            .into_iter()
+            .chain(
+                self.variables
+                    .iter()
+                    .map(|variable| self.value(step, variable)),
+            )
+            .map(|field| csv_field(&field))
+            .collect::<Vec<_>>()
+            .join(","))
+    }
+}
+
+/*
+ * The steps as a numbered list of element names, for manual testing.
+ */
+#[derive(Default)]
+pub struct TextWriter {
+    count: usize,
+}
+
+impl StepWriter for TextWriter {
+    fn step(&mut self, _machine: &Machine, step: &Step) -> Result<String, String> {
+        self.count += 1;
+        Ok(format!("{}. {}", self.count, element_name(step)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Keyword {
+    Given,
+    When,
+    Then,
+}
+
+/*
+ * The steps as a Gherkin scenario. Edges are actions written as When, and vertices are states
+ * written as Then, except for the vertices before the first edge, which are the Given.
+ * A keyword following itself becomes And.
+ */
+pub struct GherkinWriter {
+    feature: String,
+    edges: BTreeSet<Position>,
+    previous: Option<Keyword>,
+}
+
+impl GherkinWriter {
+    pub fn new(machine: &Machine) -> Self {
+        let mut edges = BTreeSet::new();
+        let mut names = Vec::new();
+        for (key, ctx) in &machine.contexts {
+            names.push(ctx.model().name.clone().unwrap_or_else(|| key.clone()));
+            edges.extend(ctx.model().edges.keys().map(|id| Position {
+                model_id: key.clone(),
+                element_id: id.clone(),
+            }));
+        }
+        Self {
+            feature: names.join(", "),
+            edges,
+            previous: None,
+        }
+    }
+}
+
+impl StepWriter for GherkinWriter {
+    fn header(&self) -> Option<String> {
+        Some(format!(
+            "Feature: {}\n\n  Scenario: A generated path",
+            self.feature
+        ))
+    }
+
+    fn step(&mut self, _machine: &Machine, step: &Step) -> Result<String, String> {
+        let keyword = if self.edges.contains(&step.position) {
+            Keyword::When
+        } else if matches!(self.previous, None | Some(Keyword::Given)) {
+            Keyword::Given
+        } else {
+            Keyword::Then
+        };
+        let word = match keyword {
+            _ if self.previous == Some(keyword) => "And",
+            Keyword::Given => "Given",
+            Keyword::When => "When",
+            Keyword::Then => "Then",
+        };
+        self.previous = Some(keyword);
+        Ok(format!("    {} {}", word, element_name(step)))
+    }
+}
+
+pub fn writer(format: Format, machine: &Machine) -> Box<dyn StepWriter> {
+    match format {
+        Format::Json(options) => Box::new(JsonWriter::new(options)),
+        Format::Csv => Box::new(CsvWriter::new(machine)),
+        Format::Text => Box::<TextWriter>::default(),
+        Format::Gherkin => Box::new(GherkinWriter::new(machine)),
+    }
+}
+
+/*
+ * Walks the models until all are fullfilled, printing each step in the format.
+ */
+pub fn walk(machine: &mut Machine, format: Format) -> Result<(), String> {
+    let mut writer = writer(format, machine);
+    if let Some(header) = writer.header() {
+        println!("{}", header);
+    }
+    machine.walk_as(|machine, step| {
+        println!("{}", writer.step(machine, step)?);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn resource_path(resource: &str) -> std::path::PathBuf {
+        let mut path = std::path::PathBuf::new();
+        path.push(env!("CARGO_MANIFEST_DIR"));
+        path.push("..");
+        path.push("..");
+        path.push("resources");
+        path.push("models");
+        path.push(resource);
+        path
+    }
+
+    fn login_machine() -> Machine {
+        let models = crate::json_read::read(resource_path("login.json").to_str().unwrap())
+            .expect("Expected the test file to be loaded");
+        let mut machine = Machine::new();
+        assert!(machine.load_models(models).is_ok());
+        machine
+    }
+
+    /*
+     * Returns the first steps of a walk through the login model, in the format.
+     */
+    fn lines(format: Format, count: usize) -> Vec<String> {
+        let mut machine = login_machine();
+        let mut writer = writer(format, &machine);
+        let mut lines: Vec<String> = writer.header().into_iter().collect();
+        let steps: Vec<Step> = machine
+            .steps()
+            .take(count)
+            .map(|step| step.expect("Expected a step"))
+            .collect();
+        for step in steps {
+            lines.push(writer.step(&machine, &step).expect("Expected a line"));
+        }
+        lines
+    }
+
+    fn step() -> Step {
+        serde_json::from_str(
+            r#"{
+                "model_name": "Login",
+                "element_name": "e_Init",
+                "position": {"model_id": "login", "element_id": "e1"},
+                "data": [
+                    {"name": "user", "value": "bob"},
+                    {"name": "validLogin", "value": false}
+                ],
+                "global_data": [{"name": "count", "value": 2}],
+                "requirements": []
+            }"#,
+        )
+        .expect("Expected a step")
+    }
+
+    #[test]
+    fn graphwalker_step() {
+        assert_eq!(
+            serde_json::to_string(&GraphWalkerStep::new(&step(), false, None)).unwrap(),
+            r#"{"currentElementName":"e_Init"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&GraphWalkerStep::new(&step(), true, None)).unwrap(),
+            r#"{"modelName":"Login","currentElementID":"e1","currentElementName":"e_Init","data":[{"user":"bob"},{"validLogin":"false"},{"global.count":"2"}]}"#
+        );
+
+        let unvisited = Unvisited {
+            number_of_elements: 3,
+            number_of_unvisited_elements: 1,
+            unvisited_elements: vec![UnvisitedElement {
+                element_id: "v2".to_string(),
+                element_name: "v_Browse".to_string(),
+            }],
+        };
+        assert_eq!(
+            serde_json::to_string(&GraphWalkerStep::new(&step(), false, Some(unvisited))).unwrap(),
+            r#"{"currentElementName":"e_Init","numberOfElements":3,"numberOfUnvisitedElements":1,"unvisitedElements":[{"elementName":"v_Browse","elementId":"v2"}]}"#
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            lines(Format::Json(JsonOptions::default()), 1),
+            vec![
+                r#"{"model_name":"Login","element_name":"v_ClientNotRunning","position":{"model_id":"login","element_id":"n1"},"data":[{"name":"rememberMe","value":false},{"name":"validLogin","value":false}],"requirements":[]}"#
+            ]
+        );
+
+        let options = JsonOptions {
+            schema: Schema::GraphWalker,
+            verbose: false,
+            unvisited: true,
+        };
+        let line = &lines(Format::Json(options), 1)[0];
+        assert!(line.starts_with(
+            r#"{"currentElementName":"v_ClientNotRunning","numberOfElements":11,"numberOfUnvisitedElements":10,"#
+        ));
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            lines(Format::Csv, 2),
+            vec![
+                "step,model_id,model_name,element_id,element_name,rememberMe,validLogin",
+                "1,login,Login,n1,v_ClientNotRunning,false,false",
+                "2,login,Login,e1,e_StartClient,false,false",
+            ]
+        );
+
+        let mut writer = CsvWriter {
+            count: 0,
+            variables: vec![
+                "user".to_string(),
+                "missing".to_string(),
+                "global.count".to_string(),
+            ],
+        };
+        assert_eq!(
+            writer.header(),
+            Some(
+                "step,model_id,model_name,element_id,element_name,user,missing,global.count"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            writer.step(&Machine::new(), &step()),
+            Ok("1,login,Login,e1,e_Init,bob,,2".to_string())
+        );
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            lines(Format::Text, 3),
+            vec![
+                "1. v_ClientNotRunning",
+                "2. e_StartClient",
+                "3. v_LoginPrompted"
+            ]
+        );
+    }
+
+    #[test]
+    fn gherkin() {
+        assert_eq!(
+            lines(Format::Gherkin, 3),
+            vec![
+                "Feature: Login\n\n  Scenario: A generated path",
+                "    Given v_ClientNotRunning",
+                "    When e_StartClient",
+                "    Then v_LoginPrompted",
+            ]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+// Each benchmark uses its own part of these helpers. Run them with:
+// cargo bench -p machine --features bench
+#![allow(dead_code)]
+
+use graph::Models;
+use machine::synthetic;
+
+/*
+ * The sizes, in elements, of the synthetic models the benchmarks run on.
+ */
+pub const SYNTHETIC_SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+pub const RESOURCES: [&str; 3] = ["login.json", "petclinic.json", "SuperLarge.json"];
+
+pub fn resource_path(resource: &str) -> String {
+    let mut path = std::path::PathBuf::new();
+    path.push(env!("CARGO_MANIFEST_DIR"));
+    path.push("..");
+    path.push("..");
+    path.push("resources");
+    path.push("models");
+    path.push(resource);
+    path.to_str().unwrap().to_string()
+}
+
+pub fn read_resource(resource: &str) -> Models {
+    io::read(&resource_path(resource)).expect("Expected the models to be read")
+}
+
+/*
+ * A strongly connected model of `size` elements, where a quarter of the elements are vertices.
+ */
+pub fn synthetic_models(size: usize, generator: &str) -> Models {
+    let mut model = synthetic::strongly_connected_model("synthetic", size / 4, size - size / 4, 1)
+        .expect("Expected a synthetic model");
+    model.generator = Some(generator.to_string());
+    synthetic::models_of(model)
+}
@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod common;
+
+/*
+ * Measures io::read, on the shipped resources and on synthetic models written to json.
+ */
+fn read(c: &mut Criterion) {
+    let mut files: Vec<(String, String)> = common::RESOURCES
+        .iter()
+        .map(|resource| (resource.to_string(), common::resource_path(resource)))
+        .collect();
+    for size in common::SYNTHETIC_SIZES {
+        let mut path = std::env::temp_dir();
+        path.push(format!("graphwalker_bench_synthetic_{}.json", size));
+        let json = serde_json::to_string(&common::synthetic_models(size, "random(length(1))"))
+            .expect("Expected the models as json");
+        std::fs::write(&path, json).expect("Expected the models to be written");
+        files.push((
+            format!("synthetic/{}", size),
+            path.to_str().unwrap().to_string(),
+        ));
+    }
+
+    let mut group = c.benchmark_group("read");
+    group.sample_size(10);
+    for (name, file) in &files {
+        let bytes = std::fs::metadata(file).expect("Expected the file").len();
+        group.throughput(Throughput::Bytes(bytes));
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| io::read(file).expect("Expected the models to be read"))
+        });
+    }
+    group.finish();
+
+    for (name, file) in files {
+        if name.starts_with("synthetic") {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+criterion_group!(benches, read);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use graph::Models;
+use machine::Machine;
+use std::time::{Duration, Instant};
+
+mod common;
+
+const STEPS: u64 = 10_000;
+
+fn load(models: Models) -> Machine {
+    let mut machine = Machine::new();
+    machine.seed(1);
+    machine
+        .load_models(models)
+        .expect("Expected the models to be loaded");
+    machine
+}
+
+/*
+ * Measures the throughput of Machine::step, in steps per second.
+ */
+fn step(c: &mut Criterion) {
+    let mut cases: Vec<(String, Models)> = common::RESOURCES
+        .iter()
+        .map(|resource| (resource.to_string(), common::read_resource(resource)))
+        .collect();
+    for size in common::SYNTHETIC_SIZES {
+        cases.push((
+            format!("synthetic/{}", size),
+            common::synthetic_models(size, "random(length(1))"),
+        ));
+    }
+
+    let mut group = c.benchmark_group("step");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(STEPS));
+    for (name, models) in cases {
+        let mut machine = load(models);
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            // The reset is left out of the measurement, it has a benchmark of its own
+            b.iter_custom(|iterations| {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iterations {
+                    machine.reset().expect("Expected the machine to be reset");
+                    let start = Instant::now();
+                    for _ in 0..STEPS {
+                        machine.step().expect("Expected a step");
+                    }
+                    elapsed += start.elapsed();
+                }
+                elapsed
+            })
+        });
+    }
+    group.finish();
+}
+
+/*
+ * Measures Machine::reset, which grows with the number of elements in the models.
+ */
+fn reset(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reset");
+    group.sample_size(10);
+    for size in common::SYNTHETIC_SIZES {
+        let mut machine = load(common::synthetic_models(size, "random(length(1))"));
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| machine.reset().expect("Expected the machine to be reset"))
+        });
+    }
+    group.finish();
+}
+
+/*
+ * Measures whole walks, from reset until the generator is fullfilled, for each generator.
+ */
+fn generators(c: &mut Criterion) {
+    let random = format!("random(length({}))", STEPS);
+    let generators = [
+        ("random", random.as_str()),
+        ("predefined_path", "predefined_path(predefined_path)"),
+    ];
+
+    let mut group = c.benchmark_group("generator");
+    group.sample_size(10);
+    for size in common::SYNTHETIC_SIZES {
+        for (name, generator) in generators {
+            let mut machine = load(common::synthetic_models(size, generator));
+
+            // The length of a walk is known once one has been made
+            let mut steps = 0;
+            machine
+                .walk_with(|_| {
+                    steps += 1;
+                    Ok(())
+                })
+                .expect("Expected a walk");
+            group.throughput(Throughput::Elements(steps));
+
+            group.bench_function(BenchmarkId::new(name, size), |b| {
+                b.iter(|| {
+                    machine.walk_with(|_| Ok(())).expect("Expected a walk");
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step, reset, generators);
+criterion_main!(benches);
@@ -0,0 +1,399 @@
+use graph::Model;
+use std::collections::{BTreeMap, HashMap};
+
+/*
+ * The highest dependency an edge can have.
+ */
+pub const MAX_DEPENDENCY: u32 = 100;
+
+/*
+ * The vertices and edges of a model, interned into dense handles, together with how many
+ * times each element has been visited. The edges get the handles before the vertices.
+ * Out edges, shared states and names are looked up once, when the model is loaded.
+ * Coverage is kept up to date on every visit, so asking for it does not scan the model.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Elements {
+    ids: Vec<String>,
+    handles: HashMap<String, usize>,
+    edge_count: usize,
+    out_edges: Vec<Vec<usize>>,
+    targets: Vec<Option<usize>>,
+    names: HashMap<String, Vec<usize>>,
+    shared_state_of: Vec<Option<String>>,
+    shared_states: BTreeMap<String, Vec<usize>>,
+    requirements: Vec<String>,
+    requirements_of: Vec<Vec<usize>>,
+    dependencies: Vec<usize>,
+    edges_by_dependency: Vec<usize>,
+
+    visits: Vec<u32>,
+    total_visits: u64,
+    visited_edges: usize,
+    visited_vertices: usize,
+    requirement_visits: Vec<u32>,
+    visited_requirements: usize,
+    visited_by_dependency: Vec<usize>,
+}
+
+impl Elements {
+    pub fn new(model: &Model) -> Self {
+        let mut elements = Self::default();
+        for id in model.edges.keys().chain(model.vertices.keys()) {
+            elements.handles.insert(id.clone(), elements.ids.len());
+            elements.ids.push(id.clone());
+        }
+        elements.edge_count = model.edges.len();
+
+        let len = elements.ids.len();
+        elements.out_edges = vec![Vec::new(); len];
+        elements.targets = vec![None; len];
+        for (handle, edge) in model.edges.values().enumerate() {
+            let source = edge
+                .source_vertex_id
+                .as_ref()
+                .and_then(|id| elements.handle(id));
+            if let Some(source) = source {
+                elements.out_edges[source].push(handle);
+            }
+            elements.targets[handle] = edge
+                .target_vertex_id
+                .as_ref()
+                .and_then(|id| elements.handle(id));
+        }
+
+        for (name, ids) in model.element_ids_by_name() {
+            let handles = ids.iter().filter_map(|id| elements.handle(id)).collect();
+            elements.names.insert(name, handles);
+        }
+
+        elements.shared_state_of = vec![None; len];
+        for (id, vertex) in &model.vertices {
+            if let (Some(name), Some(handle)) = (&vertex.shared_state, elements.handle(id)) {
+                elements.shared_state_of[handle] = Some(name.clone());
+                elements
+                    .shared_states
+                    .entry(name.clone())
+                    .or_default()
+                    .push(handle);
+            }
+        }
+
+        let mut requirement_handles: BTreeMap<String, usize> = BTreeMap::new();
+        for requirement in model.requirements() {
+            requirement_handles.insert(requirement.clone(), elements.requirements.len());
+            elements.requirements.push(requirement);
+        }
+        let edges = model.edges.values().map(|e| &e.requirements);
+        let vertices = model.vertices.values().map(|v| &v.requirements);
+        elements.requirements_of = edges
+            .chain(vertices)
+            .map(|reqs| reqs.iter().map(|req| requirement_handles[req]).collect())
+            .collect();
+
+        // Edges are counted by their dependency, so coverage at a threshold is a sum of counts
+        elements.edges_by_dependency = vec![0; MAX_DEPENDENCY as usize + 1];
+        for edge in model.edges.values() {
+            let dependency = edge.dependency.unwrap_or(0).min(MAX_DEPENDENCY) as usize;
+            elements.dependencies.push(dependency);
+            elements.edges_by_dependency[dependency] += 1;
+        }
+
+        elements.reset();
+        elements
+    }
+
+    /*
+     * Clears all visits.
+     */
+    pub fn reset(&mut self) {
+        self.visits = vec![0; self.ids.len()];
+        self.total_visits = 0;
+        self.visited_edges = 0;
+        self.visited_vertices = 0;
+        self.requirement_visits = vec![0; self.requirements.len()];
+        self.visited_requirements = 0;
+        self.visited_by_dependency = vec![0; self.edges_by_dependency.len()];
+    }
+
+    pub fn handle(&self, id: &str) -> Option<usize> {
+        self.handles.get(id).copied()
+    }
+
+    pub fn id(&self, handle: usize) -> &str {
+        &self.ids[handle]
+    }
+
+    pub fn is_edge(&self, handle: usize) -> bool {
+        handle < self.edge_count
+    }
+
+    /*
+     * Returns the handles of the edges leaving the vertex.
+     */
+    pub fn out_edges(&self, handle: usize) -> &[usize] {
+        &self.out_edges[handle]
+    }
+
+    /*
+     * Returns the handle of the target vertex of the edge.
+     */
+    pub fn target(&self, handle: usize) -> Option<usize> {
+        self.targets[handle]
+    }
+
+    /*
+     * Returns the handles of the vertices and edges with the name.
+     */
+    pub fn handles_by_name(&self, name: &str) -> &[usize] {
+        self.names.get(name).map_or(&[], |handles| handles)
+    }
+
+    /*
+     * Returns the shared state of the vertex, if it has one.
+     */
+    pub fn shared_state(&self, handle: usize) -> Option<&str> {
+        self.shared_state_of[handle].as_deref()
+    }
+
+    /*
+     * Returns the handles of the vertices in the model, grouped by their shared state name.
+     */
+    pub fn shared_states(&self) -> &BTreeMap<String, Vec<usize>> {
+        &self.shared_states
+    }
+
+    /*
+     * Counts a visit of the element, and returns how many times it has been visited.
+     */
+    pub fn visit(&mut self, handle: usize) -> u32 {
+        self.add_visits(handle, 1);
+        self.visits[handle]
+    }
+
+    fn add_visits(&mut self, handle: usize, count: u32) {
+        if count == 0 {
+            return;
+        }
+        if self.visits[handle] == 0 {
+            if self.is_edge(handle) {
+                self.visited_edges += 1;
+                self.visited_by_dependency[self.dependencies[handle]] += 1;
+            } else {
+                self.visited_vertices += 1;
+            }
+            for &req in &self.requirements_of[handle] {
+                if self.requirement_visits[req] == 0 {
+                    self.visited_requirements += 1;
+                }
+                self.requirement_visits[req] += 1;
+            }
+        }
+        self.visits[handle] += count;
+        self.total_visits += u64::from(count);
+    }
+
+    /*
+     * Replaces all visits with the visit counts of the element ids. Nothing is changed if an
+     * id is not in the model.
+     */
+    pub fn restore(&mut self, visits: &BTreeMap<String, u32>) -> Result<(), String> {
+        let mut handles = Vec::with_capacity(visits.len());
+        for (id, count) in visits {
+            match self.handle(id) {
+                Some(handle) => handles.push((handle, *count)),
+                None => return Err(format!("The element {} was not found in the model", id)),
+            }
+        }
+
+        self.reset();
+        for (handle, count) in handles {
+            self.add_visits(handle, count);
+        }
+        Ok(())
+    }
+
+    /*
+     * Returns how many times the element with the id has been visited.
+     */
+    pub fn visits(&self, id: &str) -> u32 {
+        self.handle(id).map_or(0, |handle| self.visits[handle])
+    }
+
+    /*
+     * Returns true if the element has been visited.
+     */
+    pub fn is_visited(&self, handle: usize) -> bool {
+        self.visits[handle] > 0
+    }
+
+    /*
+     * Returns the ids of all elements, and how many times they have been visited.
+     */
+    pub fn iter_visits(&self) -> impl Iterator<Item = (&String, u32)> {
+        self.ids.iter().zip(self.visits.iter().copied())
+    }
+
+    /*
+     * Returns the total number of visits of all elements.
+     */
+    pub fn total_visits(&self) -> u64 {
+        self.total_visits
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.ids.len() - self.edge_count
+    }
+
+    pub fn requirement_count(&self) -> usize {
+        self.requirements.len()
+    }
+
+    pub fn visited_edges(&self) -> usize {
+        self.visited_edges
+    }
+
+    pub fn visited_vertices(&self) -> usize {
+        self.visited_vertices
+    }
+
+    pub fn visited_requirements(&self) -> usize {
+        self.visited_requirements
+    }
+
+    /*
+     * Returns how many edges have a dependency at or above the threshold, and how many of
+     * them have been visited. An edge without a dependency has a dependency of 0.
+     */
+    pub fn dependency_edges(&self, threshold: u32) -> (usize, usize) {
+        let from = (threshold.min(MAX_DEPENDENCY) as usize).min(self.edges_by_dependency.len());
+        (
+            self.edges_by_dependency[from..].iter().sum(),
+            self.visited_by_dependency[from..].iter().sum(),
+        )
+    }
+
+    /*
+     * Returns true if any element with the requirement has been visited.
+     */
+    pub fn is_requirement_visited(&self, requirement: &str) -> bool {
+        self.requirements
+            .iter()
+            .position(|req| req == requirement)
+            .is_some_and(|index| self.requirement_visits[index] > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Edge, Vertex};
+    use pretty_assertions::assert_eq;
+
+    fn create_model() -> Model {
+        let mut model = Model::new();
+        for id in ["v1", "v2"] {
+            let mut vertex = Vertex::new().id(id.to_string());
+            vertex.name = Some("v_V".to_string());
+            vertex.requirements = vec![format!("REQ-{}", id)];
+            model.vertices.insert(id.to_string(), vertex);
+        }
+        for (id, source, target) in [("e1", "v1", "v2"), ("e2", "v2", "v1"), ("e3", "v2", "v2")] {
+            model.edges.insert(
+                id.to_string(),
+                Edge::new()
+                    .id(id.to_string())
+                    .source_vertex_id(source.to_string())
+                    .target_vertex_id(target.to_string()),
+            );
+        }
+        model
+    }
+
+    #[test]
+    fn handles() {
+        let elements = Elements::new(&create_model());
+        assert_eq!(elements.edge_count(), 3);
+        assert_eq!(elements.vertex_count(), 2);
+
+        let v2 = elements.handle("v2").unwrap();
+        assert_eq!(elements.id(v2), "v2");
+        assert!(!elements.is_edge(v2));
+        assert!(elements.is_edge(elements.handle("e1").unwrap()));
+        assert!(elements.handle("x").is_none());
+
+        let out_edges: Vec<&str> = elements
+            .out_edges(v2)
+            .iter()
+            .map(|handle| elements.id(*handle))
+            .collect();
+        assert_eq!(out_edges, vec!["e2", "e3"]);
+        assert_eq!(elements.target(elements.handle("e1").unwrap()), Some(v2));
+        assert_eq!(
+            elements.handles_by_name("v_V"),
+            [elements.handle("v1").unwrap(), v2]
+        );
+        assert!(elements.handles_by_name("x").is_empty());
+    }
+
+    #[test]
+    fn shared_states() {
+        let mut model = create_model();
+        model.vertices.get_mut("v2").unwrap().shared_state = Some("S".to_string());
+        let elements = Elements::new(&model);
+
+        let v2 = elements.handle("v2").unwrap();
+        assert_eq!(elements.shared_state(v2), Some("S"));
+        assert_eq!(elements.shared_state(elements.handle("v1").unwrap()), None);
+        assert_eq!(
+            elements.shared_states(),
+            &BTreeMap::from([("S".to_string(), vec![v2])])
+        );
+    }
+
+    #[test]
+    fn visits() {
+        let mut elements = Elements::new(&create_model());
+        let v1 = elements.handle("v1").unwrap();
+        let e1 = elements.handle("e1").unwrap();
+
+        assert_eq!(elements.visit(v1), 1);
+        assert_eq!(elements.visit(e1), 1);
+        assert_eq!(elements.visit(v1), 2);
+        assert_eq!(elements.visits("v1"), 2);
+        assert_eq!(elements.visits("v2"), 0);
+        assert_eq!(elements.total_visits(), 3);
+        assert_eq!(elements.visited_edges(), 1);
+        assert_eq!(elements.visited_vertices(), 1);
+        assert_eq!(elements.requirement_count(), 2);
+        assert_eq!(elements.visited_requirements(), 1);
+        assert!(elements.is_requirement_visited("REQ-v1"));
+        assert!(!elements.is_requirement_visited("REQ-v2"));
+
+        elements.reset();
+        assert_eq!(elements.visits("v1"), 0);
+        assert_eq!(elements.total_visits(), 0);
+        assert_eq!(elements.visited_requirements(), 0);
+    }
+
+    #[test]
+    fn restore() {
+        let mut elements = Elements::new(&create_model());
+        let visits = BTreeMap::from([("v2".to_string(), 3), ("e3".to_string(), 2)]);
+        assert!(elements.restore(&visits).is_ok());
+        assert_eq!(elements.visits("v2"), 3);
+        assert_eq!(elements.total_visits(), 5);
+        assert_eq!(elements.visited_edges(), 1);
+        assert_eq!(elements.visited_vertices(), 1);
+        assert!(elements.is_requirement_visited("REQ-v2"));
+
+        // An unknown id leaves the visits as they were
+        let visits = BTreeMap::from([("x".to_string(), 1)]);
+        assert!(elements.restore(&visits).is_err());
+        assert_eq!(elements.total_visits(), 5);
+    }
+}
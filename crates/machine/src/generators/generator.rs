@@ -1,11 +1,14 @@
-use crate::stop_condition::IsFullfilled;
+use crate::stop_condition::{Condition, IsFullfilled};
+use crate::Context;
+use serde_derive::{Deserialize, Serialize};
 
-trait GeneratorType {
+pub trait GeneratorType {
     fn generator_type(&self) -> &str;
 }
 
-struct RandomGenerator {
-    stop_conditions: Vec<Box<dyn IsFullfilled>>,
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RandomGenerator {
+    stop_conditions: Vec<Condition>,
 }
 
 impl GeneratorType for RandomGenerator {
@@ -25,12 +28,146 @@ impl IsFullfilled for RandomGenerator {
     }
 }
 
+impl Default for RandomGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RandomGenerator {
     pub fn new() -> Self {
         Self {
             stop_conditions: vec![],
         }
     }
+
+    #[must_use]
+    pub fn stop_condition(mut self, condition: Condition) -> Self {
+        self.stop_conditions.push(condition);
+        self
+    }
+
+    pub fn update(&mut self, ctx: &Context) {
+        for condition in self.stop_conditions.iter_mut() {
+            condition.update(ctx);
+        }
+    }
+}
+
+/*
+ * Follows the edges listed in the predefinedPathEdgeIds of the model, in order.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PredefinedPathGenerator {
+    stop_conditions: Vec<Condition>,
+    edge_ids: Vec<String>,
+    taken: usize,
+}
+
+impl GeneratorType for PredefinedPathGenerator {
+    fn generator_type(&self) -> &str {
+        "PredefinedPathGenerator"
+    }
+}
+
+impl IsFullfilled for PredefinedPathGenerator {
+    fn is_fullfilled(&self) -> bool {
+        for fullfilment in self.stop_conditions.iter() {
+            if !fullfilment.is_fullfilled() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl PredefinedPathGenerator {
+    pub fn new(edge_ids: Vec<String>) -> Self {
+        Self {
+            stop_conditions: vec![],
+            edge_ids,
+            taken: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn stop_condition(mut self, condition: Condition) -> Self {
+        self.stop_conditions.push(condition);
+        self
+    }
+
+    pub fn set_edge_ids(&mut self, edge_ids: Vec<String>) {
+        self.edge_ids = edge_ids;
+        self.taken = 0;
+    }
+
+    /*
+     * Returns the id of the next edge to take in the predefined path.
+     */
+    pub fn next_edge(&self) -> Option<&String> {
+        self.edge_ids.get(self.taken)
+    }
+
+    /*
+     * Moves along the predefined path when its next edge has been visited.
+     */
+    pub fn visited(&mut self, element_id: &str) {
+        if self.next_edge().map(|id| id.as_str()) == Some(element_id) {
+            self.taken += 1;
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context) {
+        for condition in self.stop_conditions.iter_mut() {
+            condition.update(ctx);
+            condition.progress(self.taken, self.edge_ids.len());
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Generator {
+    Random(RandomGenerator),
+    PredefinedPath(PredefinedPathGenerator),
+}
+
+impl GeneratorType for Generator {
+    fn generator_type(&self) -> &str {
+        match self {
+            Generator::Random(g) => g.generator_type(),
+            Generator::PredefinedPath(g) => g.generator_type(),
+        }
+    }
+}
+
+impl IsFullfilled for Generator {
+    fn is_fullfilled(&self) -> bool {
+        match self {
+            Generator::Random(g) => g.is_fullfilled(),
+            Generator::PredefinedPath(g) => g.is_fullfilled(),
+        }
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator::Random(RandomGenerator::new())
+    }
+}
+
+impl Generator {
+    pub fn visited(&mut self, element_id: &str) {
+        if let Generator::PredefinedPath(g) = self {
+            g.visited(element_id);
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context) {
+        match self {
+            Generator::Random(g) => g.update(ctx),
+            Generator::PredefinedPath(g) => g.update(ctx),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -38,14 +175,16 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
-    use crate::stop_condition::EdgeCoverage;
+    use crate::stop_condition::{EdgeCoverage, PredefinedPath};
 
     #[test]
     fn random() {
         let mut random = RandomGenerator::new();
 
         let edge_coverage = EdgeCoverage::new(0f32);
-        random.stop_conditions.push(Box::new(edge_coverage));
+        random
+            .stop_conditions
+            .push(Condition::EdgeCoverage(edge_coverage));
 
         assert_eq!(random.is_fullfilled(), true, "Should be false");
         assert_eq!(
@@ -54,4 +193,28 @@ mod tests {
             "Incorrect condition type found"
         );
     }
+
+    #[test]
+    fn predefined_path() {
+        let mut predefined_path =
+            PredefinedPathGenerator::new(vec!["e1".to_string(), "e2".to_string()]);
+        predefined_path
+            .stop_conditions
+            .push(Condition::PredefinedPath(PredefinedPath::new()));
+        let ctx = Context::new();
+
+        predefined_path.update(&ctx);
+        assert_eq!(predefined_path.next_edge(), Some(&"e1".to_string()));
+        assert_eq!(predefined_path.is_fullfilled(), false);
+
+        // Only the next edge in the path moves the generator forward
+        predefined_path.visited("e2");
+        assert_eq!(predefined_path.next_edge(), Some(&"e1".to_string()));
+        predefined_path.visited("e1");
+        predefined_path.visited("v2");
+        predefined_path.visited("e2");
+        predefined_path.update(&ctx);
+        assert_eq!(predefined_path.next_edge(), None);
+        assert_eq!(predefined_path.is_fullfilled(), true);
+    }
 }
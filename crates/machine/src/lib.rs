@@ -1,12 +1,14 @@
 use evalexpr::*;
-use graph::Edge;
 use graph::Model;
 use graph::Models;
+use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 use serde_derive::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 #[path = "stop_conditions/stop_condition.rs"]
@@ -15,6 +17,41 @@ pub mod stop_condition;
 #[path = "generators/generator.rs"]
 pub mod generator;
 
+#[path = "parsers/parser.rs"]
+pub mod parser;
+
+#[path = "statistics/statistics.rs"]
+pub mod statistics;
+
+#[path = "runners/runner.rs"]
+pub mod runner;
+
+#[path = "elements/elements.rs"]
+pub mod elements;
+
+#[cfg(any(test, feature = "bench"))]
+#[path = "synthetic/synthetic.rs"]
+pub mod synthetic;
+
+#[path = "snapshots/snapshot.rs"]
+pub mod snapshot;
+
+use elements::Elements;
+use generator::{Generator, GeneratorType};
+use snapshot::Snapshot;
+use statistics::Statistics;
+use stop_condition::IsFullfilled;
+
+/*
+ * Used for models that do not have a generator. All vertices and edges have to be visited.
+ */
+const DEFAULT_GENERATOR: &str = "random(edge_coverage(100) and vertex_coverage(100))";
+
+/*
+ * Variables with this prefix, as in `global.loggedIn`, are shared by all models.
+ */
+pub const GLOBAL_PREFIX: &str = "global.";
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug, Ord, Eq, PartialEq, PartialOrd)]
 pub struct Position {
     pub model_id: String,
@@ -43,6 +80,16 @@ pub struct Data {
     value: evalexpr::Value,
 }
 
+impl Data {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &evalexpr::Value {
+        &self.value
+    }
+}
+
 impl Serialize for Data {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -68,28 +115,208 @@ impl Serialize for Data {
     }
 }
 
-#[derive(Serialize, Clone, Default, Debug)]
+impl<'de> Deserialize<'de> for Data {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawValue {
+            Boolean(bool),
+            Int(i64),
+            Float(f64),
+            String(String),
+        }
+
+        #[derive(Deserialize)]
+        struct RawData {
+            name: String,
+            #[serde(default)]
+            value: Option<RawValue>,
+        }
+
+        let raw = RawData::deserialize(deserializer)?;
+        let value = match raw.value {
+            Some(RawValue::Boolean(b)) => evalexpr::Value::Boolean(b),
+            Some(RawValue::Int(i)) => evalexpr::Value::Int(i),
+            Some(RawValue::Float(f)) => evalexpr::Value::Float(f),
+            Some(RawValue::String(s)) => evalexpr::Value::String(s),
+            None => evalexpr::Value::Empty,
+        };
+        Ok(Data {
+            name: raw.name,
+            value,
+        })
+    }
+}
+
+/*
+ * A move between two vertices sharing the same shared state, without taking an edge.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Jump {
+    pub shared_state: String,
+    pub from: Position,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct Step {
     pub model_name: String,
     pub element_name: String,
     pub position: Position,
     pub data: Vec<Data>,
+    /// The variables shared by all models, named without the `global.` prefix.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub global_data: Vec<Data>,
+    pub requirements: Vec<String>,
+    /// Milliseconds since the unix epoch when the step was handed out.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub timestamp: u64,
+    /// Milliseconds from when the step was handed out until the next step was asked for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<f64>,
+    /// Set when the step was reached by a jump through a shared state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump: Option<Jump>,
+}
+
+fn is_zero(number: &u64) -> bool {
+    *number == 0
+}
+
+impl Step {
+    /*
+     * Returns the step without its timestamp and duration, as written by an offline walk,
+     * so that walks with the same seed write the same output.
+     */
+    pub fn without_timing(&self) -> Self {
+        Self {
+            timestamp: 0,
+            duration_ms: None,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Failure {
+    pub step: Option<Step>,
+    pub reason: String,
+}
+
+/*
+ * The error handed out by a Walk when the machine fails to generate a step.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct MachineError {
+    pub reason: String,
+}
+
+impl fmt::Display for MachineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::error::Error for MachineError {}
+
+impl From<String> for MachineError {
+    fn from(reason: String) -> Self {
+        Self { reason }
+    }
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct Profile {
     pub steps: VecDeque<Step>,
+    pub failure: Option<Failure>,
+    #[serde(skip)]
+    started: Option<Instant>,
+    #[serde(skip)]
+    last_step: Option<Instant>,
+    #[serde(skip)]
+    ended: Option<Instant>,
 }
 
 impl Profile {
     fn new() -> Self {
         Self {
             steps: VecDeque::new(),
+            failure: None,
+            started: None,
+            last_step: None,
+            ended: None,
+        }
+    }
+
+    /*
+     * Adds the step to the profile, timestamping it and closing the duration of the previous step.
+     */
+    fn push(&mut self, mut step: Step) -> Step {
+        self.close();
+        step.timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.steps.push_back(step.clone());
+        self.last_step = Some(Instant::now());
+        step
+    }
+
+    /*
+     * Sets the duration of the last step, if not already set.
+     */
+    fn close(&mut self) {
+        if let (Some(last_step), Some(step)) = (self.last_step, self.steps.back_mut()) {
+            if step.duration_ms.is_none() {
+                let now = Instant::now();
+                step.duration_ms = Some(now.duration_since(last_step).as_secs_f64() * 1000f64);
+                self.ended = Some(now);
+            }
         }
     }
 
-    fn push(&mut self, step: Step) {
-        self.steps.push_back(step);
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+        self.last_step = None;
+        self.ended = None;
+    }
+
+    /*
+     * The wall-clock time from the start of the run until the duration of the last step was set.
+     */
+    pub fn duration(&self) -> Duration {
+        match (self.started, self.ended) {
+            (Some(started), Some(ended)) => ended.duration_since(started),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /*
+     * Returns the steps that were reached by a jump through a shared state.
+     */
+    pub fn jumps(&self) -> impl Iterator<Item = &Step> {
+        self.steps.iter().filter(|step| step.jump.is_some())
+    }
+
+    /*
+     * Returns the `count` steps that took the longest time, slowest first.
+     */
+    pub fn slowest_steps(&self, count: usize) -> Vec<Step> {
+        let mut steps: Vec<Step> = self
+            .steps
+            .iter()
+            .filter(|s| s.duration_ms.is_some())
+            .cloned()
+            .collect();
+        steps.sort_by(|a, b| {
+            b.duration_ms
+                .partial_cmp(&a.duration_ms)
+                .unwrap_or(Ordering::Equal)
+        });
+        steps.truncate(count);
+        steps
     }
 }
 
@@ -97,8 +324,8 @@ impl Profile {
 pub struct Context {
     id: String,
     model: Model,
-    fullfillment: f32,
-    visited_elements: BTreeMap<String, u32>,
+    generator: Generator,
+    elements: Elements,
     eval_context: evalexpr::HashMapContext,
 }
 
@@ -107,14 +334,22 @@ impl Context {
         Self {
             id: "".to_string(),
             model: Model::new(),
-            fullfillment: 0f32,
-            visited_elements: BTreeMap::new(),
+            generator: Generator::default(),
+            elements: Elements::default(),
             eval_context: evalexpr::HashMapContext::default(),
         }
     }
+
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
+    pub fn elements(&self) -> &Elements {
+        &self.elements
+    }
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 pub enum MachineStatus {
     #[default]
     NotStarted,
@@ -129,9 +364,16 @@ pub struct Machine {
     pub profile: Profile,
     current_pos: Position,
     pub start_pos: Position,
+    start_override: Option<Position>,
     pub status: MachineStatus,
     walk_this_way: VecDeque<Position>,
     unvisited_edges: Vec<Position>,
+    shared_states: BTreeMap<String, Vec<Position>>,
+    jump_probability: Option<f32>,
+    pending_jump: Option<Jump>,
+    leaving_vertex: bool,
+    global_data: BTreeMap<String, evalexpr::Value>,
+    rng: fastrand::Rng,
 }
 
 impl Machine {
@@ -142,40 +384,24 @@ impl Machine {
             profile: Profile::new(),
             current_pos: Position::default(),
             start_pos: Position::default(),
+            start_override: None,
             status: MachineStatus::NotStarted,
             walk_this_way: VecDeque::default(),
             unvisited_edges: Vec::default(),
+            shared_states: BTreeMap::new(),
+            jump_probability: None,
+            pending_jump: None,
+            leaving_vertex: false,
+            global_data: BTreeMap::new(),
+            rng: fastrand::Rng::new(),
         }
     }
 
     /*
-     * Calculates if the specific model has covered the models given their stop conditions
+     * Checks if the specific model has fullfilled the stop conditions of its generator
      */
-    fn get_fullfilment(&self, ctx: &Context) -> f32 {
-        let element_count = ctx.visited_elements.len();
-
-        if element_count == 0 {
-            return 1f32;
-        }
-
-        let visited_count = ctx
-            .visited_elements
-            .iter()
-            .filter(|(_k, v)| v > &&0)
-            .count();
-        log::debug!(
-            "Fullfillment for model: {:?} is {:?}",
-            ctx.id,
-            visited_count as f32 / element_count as f32,
-        );
-        visited_count as f32 / element_count as f32
-    }
-
     fn is_fullfilled(&self, ctx: &Context) -> bool {
-        if self.get_fullfilment(ctx) < ctx.fullfillment {
-            return false;
-        }
-        true
+        ctx.generator.is_fullfilled()
     }
 
     pub fn is_all_fullfilled(&self) -> bool {
@@ -194,6 +420,7 @@ impl Machine {
         log::debug!("Position: {:?}", position);
         let mut step = Step {
             position: position.clone(),
+            jump: self.pending_jump.take(),
             ..Default::default()
         };
 
@@ -204,29 +431,30 @@ impl Machine {
             if let Some(name) = ctx.model.get_name_for_id(&position.element_id) {
                 step.element_name = name;
             }
+            step.requirements = ctx.model.get_requirements_for_id(&position.element_id);
 
             if ctx.eval_context.iter_variables().len() > 0 {
-                for (n, v) in ctx.eval_context.iter_variables() {
-                    let data = Data { name: n, value: v };
-                    step.data.push(data);
-                }
+                step.data = Self::data_of(ctx);
                 log::debug!("Data: {:?}", step);
             }
+            step.global_data = Self::global_data_of(&self.global_data);
 
-            if let Some(value) = ctx.visited_elements.get(&step.position.element_id) {
-                let visited = value + 1;
-                ctx.visited_elements
-                    .insert(step.clone().position.element_id, visited);
+            if let Some(handle) = ctx.elements.handle(&step.position.element_id) {
+                ctx.elements.visit(handle);
+
+                let mut generator = std::mem::take(&mut ctx.generator);
+                generator.visited(&step.position.element_id);
+                generator.update(ctx);
+                ctx.generator = generator;
             } else {
                 let msg = format!(
-                    "Expected the key {:?} to be found in unvisited_elements",
-                    step.clone()
+                    "Expected the element {:?} to be found in the model",
+                    step.position
                 );
                 log::error!("{}", msg);
                 return Err(msg);
             }
-            self.profile.push(step.clone());
-            Ok(step)
+            Ok(self.profile.push(step))
         } else {
             let msg = format!(
                 "The model id {:?} was not found in the machine",
@@ -237,118 +465,408 @@ impl Machine {
         }
     }
 
+    /*
+     * Returns the variables of the context as a list of Data, sorted by name.
+     */
+    fn data_of(ctx: &Context) -> Vec<Data> {
+        let mut data: Vec<Data> = ctx
+            .eval_context
+            .iter_variables()
+            .map(|(name, value)| Data { name, value })
+            .collect();
+        data.sort_by(|a, b| a.name.cmp(&b.name));
+        data
+    }
+
+    fn global_data_of(global_data: &BTreeMap<String, evalexpr::Value>) -> Vec<Data> {
+        global_data
+            .iter()
+            .map(|(name, value)| Data {
+                name: name.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    }
+
+    /*
+     * Evaluates a script, action or guard in the data context of a model. Variables with the
+     * global prefix are not kept in the context of the model, but in `global_data`, which is
+     * shared by all models.
+     */
+    fn eval(
+        script: &str,
+        eval_context: &mut HashMapContext,
+        global_data: &mut BTreeMap<String, evalexpr::Value>,
+    ) -> EvalexprResult<Value> {
+        if !script.contains(GLOBAL_PREFIX) {
+            return eval_with_context_mut(script, eval_context);
+        }
+
+        let mut context = eval_context.clone();
+        for (name, value) in global_data.iter() {
+            context.set_value(format!("{}{}", GLOBAL_PREFIX, name), value.clone())?;
+        }
+        let res = eval_with_context_mut(script, &mut context);
+
+        let mut local = HashMapContext::new();
+        for (name, value) in context.iter_variables() {
+            match name.strip_prefix(GLOBAL_PREFIX) {
+                Some(global_name) => {
+                    global_data.insert(global_name.to_string(), value);
+                }
+                None => local.set_value(name, value)?,
+            }
+        }
+        *eval_context = local;
+        res
+    }
+
+    /*
+     * Returns the position of the machine. After a step into a vertex, this is the vertex,
+     * until the next step selects the edge to leave it by. Otherwise it is the position the
+     * machine will step into next.
+     */
+    pub fn current_position(&self) -> &Position {
+        &self.current_pos
+    }
+
+    /*
+     * Returns the coverage, visit counts and timings of the run so far.
+     */
+    pub fn statistics(&self) -> Statistics {
+        Statistics::new(self)
+    }
+
+    /*
+     * Returns the data (variables) of the model with id: `model_id`
+     */
+    pub fn get_data(&self, model_id: &str) -> Result<Vec<Data>, String> {
+        match self.contexts.get(model_id) {
+            Some(ctx) => Ok(Self::data_of(ctx)),
+            None => {
+                let msg = format!("The model id {:?} was not found in the machine", model_id);
+                log::error!("{}", msg);
+                Err(msg)
+            }
+        }
+    }
+
+    /*
+     * Returns the data (variables) shared by all models, named without the global prefix.
+     */
+    pub fn get_global_data(&self) -> Vec<Data> {
+        Self::global_data_of(&self.global_data)
+    }
+
+    /*
+     * Evaluates `script`, for example: "validLogin=true;", in the data context
+     * of the model with id: `model_id`. Variables prefixed with `global.` are shared by all models.
+     */
+    pub fn set_data(&mut self, model_id: &str, script: &str) -> Result<(), String> {
+        let ctx = match self.contexts.get_mut(model_id) {
+            Some(ctx) => ctx,
+            None => {
+                let msg = format!("The model id {:?} was not found in the machine", model_id);
+                log::error!("{}", msg);
+                return Err(msg);
+            }
+        };
+
+        log::debug!("Will set data: {:?}", script);
+        match Self::eval(script, &mut ctx.eval_context, &mut self.global_data) {
+            Ok(value) => {
+                log::debug!("Script evaluated to: {:?}", value);
+                Ok(())
+            }
+            Err(err) => {
+                let msg = format!(
+                    "Evaluating script {:?}, failed with error: {:?}",
+                    script, err
+                );
+                log::error!("{}", msg);
+                Err(msg)
+            }
+        }
+    }
+
     pub fn reset(&mut self) -> Result<(), String> {
         log::debug!("Resetting the machine");
-        log::info!("The seed is: {:?}", fastrand::get_seed());
+        log::info!("The seed is: {:?}", self.rng.get_seed());
+        // Each walk is profiled on its own
+        self.profile = Profile::new();
 
+        // The model actions set up the data of each model, once, before the walk starts
+        self.global_data = BTreeMap::new();
         for ctx in self.contexts.values_mut() {
             ctx.eval_context = HashMapContext::default();
+            log::debug!("Will run the model actions of model id: {}", ctx.id);
+            Self::run_actions(
+                &ctx.model.actions,
+                &mut ctx.eval_context,
+                &mut self.global_data,
+            )?;
+        }
 
-            for action in &ctx.model.actions {
-                log::debug!("Will run model action: {:?}", action);
+        self.start_pos = match &self.start_override {
+            Some(start_pos) => start_pos.clone(),
+            None => self.start_position_of_models()?,
+        };
 
-                match eval_with_context_mut(action, &mut ctx.eval_context) {
-                    Ok(value) => {
-                        log::debug!("Action evaluated to: {:?}", value);
-                    }
-                    Err(err) => {
-                        let msg = format!(
-                            "Evaluating action {:?}, failed with error: {:?}",
-                            action, err
-                        );
-                        log::error!("{}", msg);
-                        return Err(msg);
-                    }
-                }
+        // Reset visited elements and unvisited edges
+        self.unvisited_edges = Vec::default();
+        for (key, ctx) in &mut self.contexts {
+            ctx.elements.reset();
+            for k in ctx.model.edges.keys() {
+                self.unvisited_edges
+                    .push(Position::new(key.to_string(), k.to_string()));
             }
+
+            let mut generator = Self::parse_generator(key, &ctx.model)?;
+            generator.update(ctx);
+            ctx.generator = generator;
         }
 
-        // First check that all start element ids are the same.
-        self.start_pos = Position::default();
+        /*
+         * Check that there's a start position
+         */
+        self.check_reachable()?;
+
+        let start_pos = self.start_pos.clone();
+        self.current_pos = start_pos;
+        self.pending_jump = None;
+        self.leaving_vertex = false;
+        self.status = MachineStatus::Running;
+        self.profile.start();
+
+        Ok(())
+    }
+
+    /*
+     * Returns the start position given by the start element ids of the models.
+     * All start element ids have to be the same, and the id has to be found in exactly one
+     * model. Models using the same ids are told apart with a model-qualified start element
+     * id, as in modelId:elementId. Models without the start element are entered through
+     * shared states.
+     */
+    fn start_position_of_models(&self) -> Result<Position, String> {
+        let mut start_element_id: Option<&String> = None;
         for (key, ctx) in &self.contexts {
-            if ctx.clone().model.start_element_id.is_some() {
-                if self.start_pos.element_id.is_empty() {
-                    self.start_pos.element_id = ctx.clone().model.start_element_id.unwrap();
-                } else if self.start_pos.element_id != ctx.clone().model.start_element_id.unwrap() {
+            let Some(id) = &ctx.model.start_element_id else {
+                continue;
+            };
+            match start_element_id {
+                Some(other) if other != id => {
                     let msg = format!(
-                        "Found different starting element id's: {:?} and {:?}",
-                        self.start_pos.model_id,
-                        key.to_string()
+                        "Found different starting element id's: {:?} and {:?}, in model id: {}",
+                        other, id, key
                     );
                     log::error!("{}", msg);
                     return Err(msg);
                 }
+                _ => start_element_id = Some(id),
             }
         }
 
         // If no start elemet id is found, bail out
-        if self.start_pos.element_id.is_empty() {
+        let Some(start_element_id) = start_element_id else {
             let msg = "Did not find any start element id. Cannot contiune".to_string();
             log::error!("{}", msg);
             return Err(msg);
-        }
+        };
 
         // Find the model in which the start element id exists
+        let positions: Vec<Position> = match self.split_model_id(start_element_id) {
+            (Some(model_id), element_id) => vec![Position::new(model_id, element_id)],
+            (None, element_id) => self
+                .contexts
+                .iter()
+                .filter(|(_, ctx)| ctx.model.has_id(element_id.clone()))
+                .map(|(key, _)| Position::new(key.clone(), element_id.clone()))
+                .collect(),
+        };
+        let exists = |pos: &Position| {
+            self.contexts
+                .get(&pos.model_id)
+                .is_some_and(|ctx| ctx.model.has_id(pos.element_id.clone()))
+        };
+
+        match positions.as_slice() {
+            [start_pos] if exists(start_pos) => Ok(start_pos.clone()),
+            [] | [_] => {
+                let msg = format!(
+                    "Did not find any model in which the start element id: {:?} exists",
+                    start_element_id
+                );
+                log::error!("{}", msg);
+                Err(msg)
+            }
+            _ => {
+                let qualified: Vec<String> = positions
+                    .iter()
+                    .map(|pos| format!("{}:{}", pos.model_id, pos.element_id))
+                    .collect();
+                let msg = format!(
+                    "The start element id: {:?} exists in more than one model. Choose one with a model-qualified start element: {}",
+                    start_element_id,
+                    qualified.join(", ")
+                );
+                log::error!("{}", msg);
+                Err(msg)
+            }
+        }
+    }
+
+    /*
+     * Splits a model-qualified element id, modelId:elementId, into its parts. The id is not
+     * qualified unless the part before the colon is the id of a model in the machine.
+     */
+    fn split_model_id(&self, id: &str) -> (Option<String>, String) {
+        match id.split_once(':') {
+            Some((model_id, element_id)) if self.contexts.contains_key(model_id) => {
+                (Some(model_id.to_string()), element_id.to_string())
+            }
+            _ => (None, id.to_string()),
+        }
+    }
+
+    /*
+     * Checks that every model can be entered from the model of the start position, through
+     * shared states. A model that can not be entered has to fullfill its stop conditions
+     * from the start, else the walk would never end.
+     */
+    fn check_reachable(&self) -> Result<(), String> {
+        let mut reached = BTreeSet::from([self.start_pos.model_id.as_str()]);
+        let mut queue = vec![self.start_pos.model_id.as_str()];
+        while let Some(model_id) = queue.pop() {
+            let Some(ctx) = self.contexts.get(model_id) else {
+                continue;
+            };
+            for name in ctx.elements.shared_states().keys() {
+                for pos in self.get_other_shared_states(name) {
+                    if reached.insert(pos.model_id.as_str()) {
+                        queue.push(pos.model_id.as_str());
+                    }
+                }
+            }
+        }
+
         for (key, ctx) in &self.contexts {
-            if ctx.model.has_id(self.start_pos.element_id.clone()) {
-                self.start_pos.model_id = key.to_string();
+            if !reached.contains(key.as_str()) && !self.is_fullfilled(ctx) {
+                let msg = format!(
+                    "The model id: {} can not be reached from the start element through shared states, and would never fullfill its stop conditions",
+                    key
+                );
+                log::error!("{}", msg);
+                return Err(msg);
             }
         }
+        Ok(())
+    }
 
-        // If no model id is found for the start element, bail out
-        if self.start_pos.model_id.is_empty() {
-            let msg = format!(
-                "Did not find any model in which the start element id: {:?} exists",
-                self.start_pos.element_id
-            );
+    /*
+     * Makes walks start at `position`, instead of at the start element of the models.
+     */
+    pub fn set_start(&mut self, position: Position) -> Result<(), String> {
+        let exists = self
+            .contexts
+            .get(&position.model_id)
+            .is_some_and(|ctx| ctx.model.has_id(position.element_id.clone()));
+        if !position.is_valid() || !exists {
+            let msg = format!("The start position {:?} is not in the models", position);
             log::error!("{}", msg);
             return Err(msg);
         }
+        self.start_override = Some(position);
+        Ok(())
+    }
 
-        // Reset visited elements and unvisited edges
-        self.unvisited_edges = Vec::default();
-        for (key, ctx) in &mut self.contexts {
-            let mut visited_elements = BTreeMap::new();
-            let mut unvisited_edges = Vec::new();
-            for k in ctx.model.edges.keys() {
-                visited_elements.insert(k.to_string(), 0);
-                unvisited_edges.push(Position::new(key.to_string(), k.to_string()));
+    /*
+     * Finds the position of an element, given its id or its name. A name has to be unique
+     * in the models. The id or name can be qualified with the id of its model, as in
+     * modelId:elementId, to only look in that model.
+     */
+    pub fn find_position(&self, id_or_name: &str) -> Result<Position, String> {
+        let (model_id, element) = self.split_model_id(id_or_name);
+        let mut by_id = Vec::new();
+        let mut by_name = Vec::new();
+        for (key, ctx) in &self.contexts {
+            if model_id.as_ref().is_some_and(|model_id| model_id != key) {
+                continue;
+            }
+            if ctx.model.has_id(element.clone()) {
+                by_id.push(Position::new(key.clone(), element.clone()));
             }
-            for k in ctx.model.vertices.keys() {
-                visited_elements.insert(k.to_string(), 0);
+            for &handle in ctx.elements.handles_by_name(&element) {
+                by_name.push(Position::new(
+                    key.clone(),
+                    ctx.elements.id(handle).to_string(),
+                ));
             }
-            ctx.visited_elements = visited_elements;
-            self.unvisited_edges.extend(unvisited_edges);
         }
 
-        /*
-         * Check that there's a start position
-         */
-        let start_pos = self.start_pos.clone();
-        self.current_pos = start_pos;
-        self.status = MachineStatus::Running;
-
-        Ok(())
+        let positions = if by_id.is_empty() { by_name } else { by_id };
+        match positions.len() {
+            1 => Ok(positions[0].clone()),
+            0 => {
+                let msg = format!("No element with the id or name {:?} was found", id_or_name);
+                log::error!("{}", msg);
+                Err(msg)
+            }
+            _ => {
+                let msg = format!(
+                    "The id or name {:?} matches more than one element: {:?}. Qualify it with the model id, as in modelId:elementId",
+                    id_or_name, positions
+                );
+                log::error!("{}", msg);
+                Err(msg)
+            }
+        }
     }
 
     /*
      * Return a list of vertices that has matching share state name as: `shared_state_str`
      */
-    fn get_other_shared_states(&self, shared_state_str: String) -> Vec<Position> {
-        let mut list: Vec<Position> = Vec::new();
-        for ctx in self.contexts.values() {
-            for (k, v) in &ctx.model.vertices {
-                if let Some(name) = &v.shared_state {
-                    if name.cmp(&shared_state_str) == Ordering::Equal {
-                        list.push(Position {
-                            model_id: ctx.id.clone(),
-                            element_id: k.clone(),
-                        });
+    fn get_other_shared_states(&self, shared_state_str: &str) -> &[Position] {
+        self.shared_states
+            .get(shared_state_str)
+            .map_or(&[], |positions| positions)
+    }
+
+    /*
+     * Creates the generator, with its stop conditions, from the generator string of the model.
+     */
+    fn parse_generator(key: &str, model: &Model) -> Result<Generator, String> {
+        let generator_str = model
+            .generator
+            .clone()
+            .unwrap_or(DEFAULT_GENERATOR.to_string());
+        match parser::generator(&generator_str) {
+            Ok(mut generator) => {
+                if let Generator::PredefinedPath(predefined_path) = &mut generator {
+                    if model.predefined_path_edge_ids.is_empty() {
+                        let msg = format!(
+                            "The model id: {} uses a predefined path, but has no predefinedPathEdgeIds",
+                            key
+                        );
+                        log::error!("{}", msg);
+                        return Err(msg);
                     }
+                    predefined_path.set_edge_ids(model.predefined_path_edge_ids.clone());
                 }
+                log::debug!(
+                    "Model id: {} uses {} with: {:?}",
+                    key,
+                    generator.generator_type(),
+                    generator_str
+                );
+                Ok(generator)
+            }
+            Err(err) => {
+                let msg = format!("The generator of model id: {} is not valid. {}", key, err);
+                log::error!("{}", msg);
+                Err(msg)
             }
         }
-        list
     }
 
     pub fn load_models(&mut self, models: Models) -> Result<(), String> {
@@ -360,69 +878,215 @@ impl Machine {
                 return Err(msg);
             }
 
+            if let Some((id, edge)) = model
+                .edges
+                .iter()
+                .find(|(_, e)| e.dependency.is_some_and(|d| d > elements::MAX_DEPENDENCY))
+            {
+                let msg = format!(
+                    "The dependency of edge {} in model id: {} has to be between 0 and {}, got: {}",
+                    id,
+                    key,
+                    elements::MAX_DEPENDENCY,
+                    edge.dependency.unwrap_or_default()
+                );
+                log::error!("{}", msg);
+                return Err(msg);
+            }
+
+            let generator = Self::parse_generator(&key, &model)?;
+
             let mut context = Context::new();
             context.id = key.clone();
-            context.model = model.clone();
-            context.fullfillment = 1f32;
+            context.elements = Elements::new(&model);
+            context.model = model;
+            context.generator = generator;
 
             self.contexts.insert(key.clone(), context);
         }
+
+        // Group the vertices of all models by their shared state
+        self.shared_states = BTreeMap::new();
+        for (key, ctx) in &self.contexts {
+            for (name, handles) in ctx.elements.shared_states() {
+                self.shared_states
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(handles.iter().map(|&handle| {
+                        Position::new(key.clone(), ctx.elements.id(handle).to_string())
+                    }));
+            }
+        }
         Ok(())
     }
 
     /*/
      * From current position, which mush represent a vertex, select the next step (edge)
      */
-    fn select_next_edge(
-        &mut self,
-        current_pos: &Position,
-        model: &mut Model,
-    ) -> Result<(), String> {
-        if let Some(vertex) = model.vertices.get(&current_pos.element_id) {
-            // Build a list of candidates of edges to select
-            // Look for shared_states
-            let mut candidates: Vec<Position> = Vec::new();
-            if let Some(name) = vertex.clone().shared_state {
-                candidates.clone_from(&self.get_other_shared_states(name));
-                // Remove the current vertex from the candidate list, since we are already at it.
-                let index = candidates
-                    .iter()
-                    .position(|x| *x == current_pos.clone())
-                    .unwrap();
-                candidates.remove(index);
-            }
-
-            for e in model.out_edges(current_pos.element_id.clone()) {
-                let pos = Position {
-                    model_id: current_pos.model_id.clone(),
-                    element_id: e.id.clone().unwrap(),
-                };
-                if self.is_selectable(model.id.clone().unwrap(), &e) {
-                    log::trace!("Adding {:?} to the candidates list", pos);
-                    candidates.push(pos);
-                }
-            }
+    fn select_next_edge(&mut self, current_pos: &Position, vertex: usize) -> Result<(), String> {
+        // The stop conditions of each model are checked on their own. A model that is
+        // fullfilled is left through a shared state, for a model that is not, if possible.
+        if let Some((shared_state, positions)) = self.shared_states_to_leave_by(current_pos, vertex)
+        {
+            let pos = positions[self.rng.usize(..positions.len())].clone();
+            log::debug!(
+                "The model id: {} is fullfilled, jumping from {:?} to {:?}",
+                current_pos.model_id,
+                current_pos,
+                pos
+            );
+            self.pending_jump = Some(Jump {
+                shared_state,
+                from: current_pos.clone(),
+            });
+            self.current_pos = pos;
+            return Ok(());
+        }
+
+        let Some(ctx) = self.contexts.get_mut(&current_pos.model_id) else {
+            let msg = format!("Could not find model id: {}", &current_pos.model_id);
+            log::warn!("{}", msg);
+            return Err(msg);
+        };
 
-            if candidates.is_empty() {
-                // Vertex is a cul-de-sac
-                let msg = format!("Vertex {} is a cul-de-sac", current_pos.element_id);
-                log::warn!("{}", msg);
+        // A predefined path decides the next edge by itself
+        if let Generator::PredefinedPath(predefined_path) = &ctx.generator {
+            let Some(edge_id) = predefined_path.next_edge() else {
+                let msg = format!(
+                    "The predefined path of model id: {} is exhausted",
+                    current_pos.model_id
+                );
+                log::error!("{}", msg);
+                return Err(msg);
+            };
+            let is_out_edge = ctx
+                .elements
+                .handle(edge_id)
+                .is_some_and(|edge| ctx.elements.out_edges(vertex).contains(&edge));
+            if !is_out_edge {
+                let msg = format!(
+                    "The edge {} in the predefined path is not an out edge of vertex {}",
+                    edge_id, current_pos.element_id
+                );
+                log::error!("{}", msg);
                 return Err(msg);
             }
+            self.current_pos = Position::new(current_pos.model_id.clone(), edge_id.clone());
+            return Ok(());
+        }
 
-            let random_index = fastrand::usize(..candidates.len());
-            self.current_pos = candidates[random_index].clone();
+        // Build a list of candidates of edges to select
+        // Look for shared_states, except the current vertex, since we are already at it.
+        let shared_state = ctx.elements.shared_state(vertex);
+        let mut shared_states: Vec<&Position> = Vec::new();
+        if let Some(positions) = shared_state.and_then(|name| self.shared_states.get(name)) {
+            shared_states.extend(positions.iter().filter(|pos| *pos != current_pos));
+        }
 
-            return Ok(());
+        let mut edges: Vec<usize> = Vec::new();
+        for &edge in ctx.elements.out_edges(vertex) {
+            let guard = ctx
+                .model
+                .edges
+                .get(ctx.elements.id(edge))
+                .and_then(|e| e.guard.as_ref());
+            if Self::guard_holds(guard, &mut ctx.eval_context, &mut self.global_data) {
+                log::trace!("Adding {:?} to the candidates list", ctx.elements.id(edge));
+                edges.push(edge);
+            }
+        }
+
+        let candidates = shared_states.len() + edges.len();
+        if candidates == 0 {
+            // Vertex is a cul-de-sac
+            let msg = format!("Vertex {} is a cul-de-sac", current_pos.element_id);
+            log::warn!("{}", msg);
+            return Err(msg);
+        }
+
+        // Without a jump probability, every shared state is one more candidate among the edges
+        let random_index = match self.jump_probability {
+            Some(probability) if !shared_states.is_empty() && !edges.is_empty() => {
+                if self.rng.f32() < probability {
+                    self.rng.usize(..shared_states.len())
+                } else {
+                    shared_states.len() + self.rng.usize(..edges.len())
+                }
+            }
+            _ => self.rng.usize(..candidates),
+        };
+
+        match shared_states.get(random_index) {
+            Some(pos) => {
+                log::debug!("Jumping from {:?} to {:?}", current_pos, pos);
+                self.pending_jump = shared_state.map(|name| Jump {
+                    shared_state: name.to_string(),
+                    from: current_pos.clone(),
+                });
+                self.current_pos = (*pos).clone();
+            }
+            None => {
+                let edge = edges[random_index - shared_states.len()];
+                self.current_pos = Position::new(
+                    current_pos.model_id.clone(),
+                    ctx.elements.id(edge).to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /*
+     * If the model of the current vertex is fullfilled, returns the shared state of the vertex,
+     * and the vertices sharing it in models that are not yet fullfilled. Only vertices with
+     * out edges are returned, since the walk could not go on in their models from the others.
+     */
+    fn shared_states_to_leave_by(
+        &self,
+        current_pos: &Position,
+        vertex: usize,
+    ) -> Option<(String, Vec<Position>)> {
+        let ctx = self.contexts.get(&current_pos.model_id)?;
+        if !self.is_fullfilled(ctx) {
+            return None;
+        }
+        let name = ctx.elements.shared_state(vertex)?;
+        let positions: Vec<Position> = self
+            .get_other_shared_states(name)
+            .iter()
+            .filter(|pos| {
+                self.contexts.get(&pos.model_id).is_some_and(|other| {
+                    !self.is_fullfilled(other)
+                        && other
+                            .elements
+                            .handle(&pos.element_id)
+                            .is_some_and(|handle| !other.elements.out_edges(handle).is_empty())
+                })
+            })
+            .cloned()
+            .collect();
+        if positions.is_empty() {
+            return None;
         }
+        Some((name.to_string(), positions))
+    }
 
-        // If reached this code, there is something fishy going on
-        let msg = format!(
-            "Could not find vertex nor edge matching the current position: {:?}",
-            current_pos
-        );
-        log::warn!("{}", msg);
-        Err(msg)
+    /*
+     * Sets the probability, between 0 and 1, of jumping through a shared state instead of
+     * taking an out edge, at a vertex where both are possible. By default a shared state is
+     * as likely to be picked as any out edge.
+     */
+    pub fn set_jump_probability(&mut self, probability: f32) -> Result<(), String> {
+        if !(0f32..=1f32).contains(&probability) {
+            let msg = format!(
+                "The jump probability has to be between 0 and 1, got: {}",
+                probability
+            );
+            log::error!("{}", msg);
+            return Err(msg);
+        }
+        self.jump_probability = Some(probability);
+        Ok(())
     }
 
     // fn popuplate_walk_this_way(&mut self) {
@@ -433,7 +1097,68 @@ impl Machine {
     //     let pos = self.unvisited_edges[random_index].clone();
     // }
 
+    /*
+     * Marks the machine as failed. The last step handed out, if any, is
+     * recorded together with the `reason` in the profile.
+     */
+    fn record_failure(&mut self, reason: &str) {
+        self.status = MachineStatus::Failed;
+        self.profile.close();
+        self.profile.failure = Some(Failure {
+            step: self.profile.steps.back().cloned(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /*
+     * Lets the caller, typically a test harness, report that the system under
+     * test failed at the current step.
+     */
+    pub fn fail(&mut self, reason: &str) -> Result<(), String> {
+        if self.status != MachineStatus::Running {
+            let msg = format!(
+                "Can only fail a running machine, the status is: {:?}",
+                self.status
+            );
+            log::error!("{}", msg);
+            return Err(msg);
+        }
+
+        log::error!("The step failed: {:?}", reason);
+        self.record_failure(reason);
+        Ok(())
+    }
+
     pub fn step(&mut self) -> Result<Step, String> {
+        if self.status == MachineStatus::Failed {
+            let msg = "The machine has failed, no more steps will be generated".to_string();
+            log::warn!("{}", msg);
+            return Err(msg);
+        }
+
+        // The edge out of a vertex is selected when the next step is asked for, and not when
+        // the vertex is stepped into, so that data set in between can steer the guards
+        if self.leaving_vertex {
+            let vertex_pos = self.current_pos.clone();
+            let Some(vertex) = self
+                .contexts
+                .get(&vertex_pos.model_id)
+                .and_then(|ctx| ctx.elements.handle(&vertex_pos.element_id))
+            else {
+                let msg = format!(
+                    "Element {} was not found in model: {}",
+                    vertex_pos.element_id, vertex_pos.model_id,
+                );
+                log::error!("{}", msg);
+                return Err(msg);
+            };
+            if let Err(err) = self.select_next_edge(&vertex_pos, vertex) {
+                log::error!("{}", err);
+                return Err(err);
+            }
+            self.leaving_vertex = false;
+        }
+
         let current_pos = self.current_pos.clone();
 
         let step = match self.log_step(&current_pos) {
@@ -468,83 +1193,76 @@ impl Machine {
         //     return Err(msg);
         // }
 
-        let mut model;
-        if let Some(ctx) = self.contexts.get(&current_pos.model_id) {
-            model = ctx.model.clone();
-        } else {
+        let Some(ctx) = self.contexts.get(&current_pos.model_id) else {
             let msg = format!("Could not find model id: {}", &current_pos.model_id);
             log::warn!("{}", msg);
             return Err(msg);
-        }
+        };
 
         // Check that the element does exist in the model
-        if !model.has_id(current_pos.clone().element_id) {
+        let Some(handle) = ctx.elements.handle(&current_pos.element_id) else {
             let msg = format!(
                 "Element {} was not found in model: {}",
-                current_pos.clone().element_id,
-                current_pos.clone().model_id,
+                current_pos.element_id, current_pos.model_id,
             );
             log::error!("{}", msg);
             return Err(msg);
-        }
+        };
 
         // If the current position represents an edge, return that edge
         // The next element is the destination vertex.
-        if let Some(edge) = model.edges.clone().get(&current_pos.clone().element_id) {
-            self.current_pos.element_id = edge.target_vertex_id.as_ref().unwrap().to_string();
+        if ctx.elements.is_edge(handle) {
+            let Some(target) = ctx.elements.target(handle) else {
+                let msg = format!("The edge {} has no target vertex", current_pos.element_id);
+                log::error!("{}", msg);
+                return Err(msg);
+            };
+            self.current_pos.element_id = ctx.elements.id(target).to_string();
             return Ok(step);
         }
 
-        // If we have not found a step yet, the next step must be a an edge.
-        match self.select_next_edge(&current_pos, &mut model) {
-            Ok(()) => {}
-            Err(err) => {
-                log::error!("{}", err);
-                return Err(err);
-            }
-        }
+        // From a vertex, the next step must be an edge, or a jump through a shared state
+        self.leaving_vertex = true;
         Ok(step)
     }
 
     /*
-     * Return the id of the context and a list of actions matching the position
-     * of the element.
+     * Runs the actions of the element at the position, in the data context of the model of
+     * the position. Element ids are only unique within a model.
      */
-    fn get_actions(&self, pos: &Position) -> (String, Vec<String>) {
-        for (k, v) in &self.contexts {
-            if let Some(edge) = v.model.edges.get(&pos.element_id) {
-                return (k.clone(), edge.actions.clone());
-            }
-            if let Some(vertex) = v.model.vertices.get(&pos.element_id) {
-                return (k.clone(), vertex.actions.clone());
-            }
-        }
-        ("".to_string(), Vec::default())
-    }
-
     fn run_action(&mut self, pos: &Position) -> Result<(), String> {
-        let (ctx_id, actions) = self.get_actions(pos);
-
-        if ctx_id.is_empty() || actions.is_empty() {
+        let Some(ctx) = self.contexts.get_mut(&pos.model_id) else {
             return Ok(());
-        }
+        };
+        let actions = if let Some(edge) = ctx.model.edges.get(&pos.element_id) {
+            &edge.actions
+        } else if let Some(vertex) = ctx.model.vertices.get(&pos.element_id) {
+            &vertex.actions
+        } else {
+            return Ok(());
+        };
+        Self::run_actions(actions, &mut ctx.eval_context, &mut self.global_data)
+    }
 
-        if let Some(ctx) = self.contexts.get_mut(&ctx_id) {
-            for action in actions {
-                log::debug!("Will run: {:?}", action);
+    fn run_actions(
+        actions: &[String],
+        eval_context: &mut HashMapContext,
+        global_data: &mut BTreeMap<String, evalexpr::Value>,
+    ) -> Result<(), String> {
+        for action in actions {
+            log::debug!("Will run: {:?}", action);
 
-                match eval_with_context_mut(&action, &mut ctx.eval_context) {
-                    Ok(value) => {
-                        log::debug!("Action evaluated to: {:?}", value);
-                    }
-                    Err(err) => {
-                        let msg = format!(
-                            "Evaluating action {:?}, failed with error: {:?}",
-                            action, err
-                        );
-                        log::error!("{}", msg);
-                        return Err(msg);
-                    }
+            match Self::eval(action, eval_context, global_data) {
+                Ok(value) => {
+                    log::debug!("Action evaluated to: {:?}", value);
+                }
+                Err(err) => {
+                    let msg = format!(
+                        "Evaluating action {:?}, failed with error: {:?}",
+                        action, err
+                    );
+                    log::error!("{}", msg);
+                    return Err(msg);
                 }
             }
         }
@@ -555,32 +1273,39 @@ impl Machine {
      * Returns true if no guard exists for an edge, or if the guard evaluates to true.
      * Else returns false
      */
-    fn is_selectable(&mut self, ctx_id: String, e: &Edge) -> bool {
-        if let Some(guard) = e.guard.clone() {
+    fn is_selectable(&mut self, ctx_id: &str, edge_id: &str) -> bool {
+        let Some(ctx) = self.contexts.get_mut(ctx_id) else {
+            return true;
+        };
+        let guard = ctx.model.edges.get(edge_id).and_then(|e| e.guard.as_ref());
+        Self::guard_holds(guard, &mut ctx.eval_context, &mut self.global_data)
+    }
+
+    fn guard_holds(
+        guard: Option<&String>,
+        eval_context: &mut HashMapContext,
+        global_data: &mut BTreeMap<String, evalexpr::Value>,
+    ) -> bool {
+        if let Some(guard) = guard {
             log::debug!("Edge has guard: {:?}", guard);
 
-            if let Some(ctx) = self.contexts.get_mut(&ctx_id) {
-                match eval_with_context_mut(&guard, &mut ctx.eval_context) {
-                    Ok(value) => match value.as_boolean() {
-                        Ok(res) => {
-                            log::debug!("The guard evaluated to: {:?}", res);
-                            return res;
-                        }
-                        Err(err) => {
-                            let msg = format!(
-                                "Evaluating guard {:?}, failed with error: {:?}",
-                                guard, err
-                            );
-                            log::error!("{}", msg);
-                            return true;
-                        }
-                    },
+            match Self::eval(guard, eval_context, global_data) {
+                Ok(value) => match value.as_boolean() {
+                    Ok(res) => {
+                        log::debug!("The guard evaluated to: {:?}", res);
+                        return res;
+                    }
                     Err(err) => {
                         let msg =
                             format!("Evaluating guard {:?}, failed with error: {:?}", guard, err);
                         log::error!("{}", msg);
                         return true;
                     }
+                },
+                Err(err) => {
+                    let msg = format!("Evaluating guard {:?}, failed with error: {:?}", guard, err);
+                    log::error!("{}", msg);
+                    return true;
                 }
             }
         }
@@ -588,48 +1313,330 @@ impl Machine {
         true
     }
 
+    /*
+     * Returns an iterator over the steps of a new walk. The machine is reset when the
+     * first step is asked for, and the iterator ends when all models are fullfilled.
+     */
+    pub fn steps(&mut self) -> Walk<'_> {
+        Walk {
+            machine: self,
+            started: false,
+        }
+    }
+
+    /*
+     * Walks the models until all are fullfilled. The steps are kept in the profile.
+     */
     pub fn walk(&mut self) -> Result<(), String> {
-        match self.reset() {
-            Ok(()) => {}
-            Err(err) => {
-                self.status = MachineStatus::Failed;
+        self.walk_with(|_| Ok(()))
+    }
+
+    /*
+     * Walks the models until all are fullfilled, handing each step to `sink`, together with
+     * the machine that took it. If the sink returns an error, the machine fails at that step.
+     */
+    pub fn walk_as<F>(&mut self, mut sink: F) -> Result<(), String>
+    where
+        F: FnMut(&Machine, &Step) -> Result<(), String>,
+    {
+        let mut walk = self.steps();
+        while let Some(res) = walk.next() {
+            let step = res.map_err(|err| err.reason)?;
+            if let Err(err) = sink(walk.machine, &step) {
+                walk.machine.record_failure(&err);
+                log::debug!("The machine has failed");
                 return Err(err);
             }
         }
+        Ok(())
+    }
+
+    /*
+     * Walks the models until all are fullfilled, handing each step to `sink`.
+     * If the sink returns an error, the machine fails at that step.
+     */
+    pub fn walk_with<F>(&mut self, mut sink: F) -> Result<(), String>
+    where
+        F: FnMut(&Step) -> Result<(), String>,
+    {
+        self.walk_as(|_, step| sink(step))
+    }
 
-        loop {
-            if self.is_all_fullfilled() {
-                log::debug!("All models are fullfilled and the machine is running");
-                self.status = MachineStatus::Ended;
-                log::debug!("The machine has ended");
-                return Ok(());
+    /*
+     * Checks that moving from the position `from` to the position `to` is a legal transition
+     * in the models. If `to` is an edge, its guard has to hold. Returns the jump, if the
+     * transition is one through a shared state.
+     */
+    fn check_transition(&mut self, from: &Position, to: &Position) -> Result<Option<Jump>, String> {
+        let ctx = match self.contexts.get(&from.model_id) {
+            Some(ctx) => ctx,
+            None => return Err(format!("Could not find model id: {}", &from.model_id)),
+        };
+        let Some(from_handle) = ctx.elements.handle(&from.element_id) else {
+            return Err(format!(
+                "Element {} was not found in model: {}",
+                from.element_id, from.model_id
+            ));
+        };
+        let to_handle = if to.model_id == from.model_id {
+            ctx.elements.handle(&to.element_id)
+        } else {
+            None
+        };
+
+        // From an edge, the next position is the target vertex of the edge
+        if ctx.elements.is_edge(from_handle) {
+            if to_handle.is_some() && ctx.elements.target(from_handle) == to_handle {
+                return Ok(None);
             }
+            return Err(format!(
+                "{:?} is not the target vertex of the edge {:?}",
+                to, from
+            ));
+        }
 
-            match self.step() {
-                Ok(step) => match serde_json::to_string(&step) {
-                    Ok(step_json_str) => {
-                        println!("{}", step_json_str);
-                        if self.status == MachineStatus::Ended {
-                            log::debug!("The machine has ended");
-                            return Ok(());
-                        }
-                    }
-                    Err(err) => {
-                        let msg = format!("Could extract the json str from step: {:?}", err);
-                        log::warn!("{}", msg);
-                        return Err(msg);
-                    }
-                },
+        // From a vertex, the next position is either an out edge of the vertex...
+        if let Some(edge) = to_handle.filter(|&handle| ctx.elements.is_edge(handle)) {
+            if !ctx.elements.out_edges(from_handle).contains(&edge) {
+                return Err(format!("{:?} is not an out edge of {:?}", to, from));
+            }
+            if !self.is_selectable(&from.model_id, &to.element_id) {
+                return Err(format!("The guard of {:?} does not hold", to));
+            }
+            return Ok(None);
+        }
+
+        // ...or a vertex sharing the same shared state
+        if let Some(name) = ctx.elements.shared_state(from_handle) {
+            if self.get_other_shared_states(name).contains(to) && to != from {
+                return Ok(Some(Jump {
+                    shared_state: name.to_string(),
+                    from: from.clone(),
+                }));
+            }
+        }
+        Err(format!("{:?} can not be reached from {:?}", to, from))
+    }
+
+    /*
+     * Re-executes a recorded path of positions, handing each step to `sink`, together with the
+     * machine. The replay starts at the first position of the path, actions are run as in a
+     * walk, and every following position has to be a legal transition from the previous one.
+     * If the sink returns an error, the machine fails at that step.
+     */
+    pub fn replay<F>(&mut self, path: Vec<Position>, mut sink: F) -> Result<(), String>
+    where
+        F: FnMut(&Machine, &Step) -> Result<(), String>,
+    {
+        let Some(first) = path.first().cloned() else {
+            let msg = "The recorded path is empty".to_string();
+            log::error!("{}", msg);
+            self.record_failure(&msg);
+            return Err(msg);
+        };
+
+        // The recorded path may have started elsewhere than at the start element
+        let start_override = self.start_override.clone();
+        let res = self.set_start(first).and_then(|_| self.reset());
+        self.start_override = start_override;
+        if let Err(err) = res {
+            let msg = format!("The recorded path can not be replayed: {}", err);
+            log::error!("{}", msg);
+            self.record_failure(&msg);
+            return Err(msg);
+        }
+
+        self.walk_this_way = VecDeque::from(path);
+        let mut previous: Option<Position> = None;
+        while let Some(pos) = self.walk_this_way.pop_front() {
+            let res = match &previous {
+                None => Ok(None),
+                Some(from) => self.check_transition(from, &pos),
+            };
+            match res {
+                Ok(jump) => self.pending_jump = jump,
                 Err(err) => {
-                    self.status = MachineStatus::Failed;
-                    log::debug!("The machine has failed");
-                    return Err(err);
+                    let msg = format!("The recorded path can not be replayed: {}", err);
+                    log::error!("{}", msg);
+                    self.walk_this_way.clear();
+                    self.record_failure(&msg);
+                    return Err(msg);
                 }
             }
+
+            self.current_pos = pos.clone();
+            let res = self
+                .log_step(&pos)
+                .and_then(|step| self.run_action(&pos).map(|_| step))
+                .and_then(|step| sink(self, &step));
+            if let Err(err) = res {
+                self.walk_this_way.clear();
+                self.record_failure(&err);
+                return Err(err);
+            }
+            previous = Some(pos);
         }
+
+        self.status = MachineStatus::Ended;
+        self.profile.close();
+        log::debug!("The replay has ended");
+        Ok(())
     }
 
     pub fn seed(&mut self, number: u64) {
-        fastrand::seed(number);
+        self.rng.seed(number);
+    }
+
+    /*
+     * Returns the state of the walk so far, which can be saved and later handed to restore.
+     */
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self)
+    }
+
+    /*
+     * Puts the machine back into the state of the snapshot, so that the walk continues exactly
+     * as it would have from where the snapshot was taken. The machine must have loaded the same
+     * models as the one the snapshot was taken from. Nothing is changed if the snapshot does
+     * not fit the models.
+     */
+    pub fn restore(&mut self, snapshot: Snapshot) -> Result<(), String> {
+        if !snapshot.models.keys().eq(self.contexts.keys()) {
+            let msg = format!(
+                "The models of the snapshot: {:?}, are not the models of the machine: {:?}",
+                snapshot.models.keys().collect::<Vec<_>>(),
+                self.contexts.keys().collect::<Vec<_>>()
+            );
+            log::error!("{}", msg);
+            return Err(msg);
+        }
+
+        let exists = |position: &Position| {
+            self.contexts
+                .get(&position.model_id)
+                .is_some_and(|ctx| ctx.model.has_id(position.element_id.clone()))
+        };
+        // The start override is used by the next reset, even if the walk was not started
+        let in_use = [&snapshot.current_position, &snapshot.start_position]
+            .into_iter()
+            .filter(|_| snapshot.status != MachineStatus::NotStarted)
+            .chain(&snapshot.start_override);
+        for position in in_use {
+            if !exists(position) {
+                let msg = format!(
+                    "The position {:?} of the snapshot is not in the models",
+                    position
+                );
+                log::error!("{}", msg);
+                return Err(msg);
+            }
+        }
+
+        // Restore into copies first, so that a failure leaves the machine as it was
+        let mut restored = Vec::new();
+        for (key, model) in snapshot.models {
+            let ctx = &self.contexts[&key];
+            let mut elements = ctx.elements.clone();
+            if let Err(err) = elements.restore(&model.visits) {
+                let msg = format!("Could not restore the model id: {}. {}", key, err);
+                log::error!("{}", msg);
+                return Err(msg);
+            }
+
+            let mut eval_context = HashMapContext::default();
+            for data in model.data {
+                if let Err(err) = eval_context.set_value(data.name.clone(), data.value) {
+                    let msg = format!(
+                        "Could not restore the data {:?} of model id: {}. {:?}",
+                        data.name, key, err
+                    );
+                    log::error!("{}", msg);
+                    return Err(msg);
+                }
+            }
+            restored.push((key, elements, eval_context, model.generator));
+        }
+
+        let global_data: BTreeMap<String, evalexpr::Value> = snapshot
+            .global_data
+            .into_iter()
+            .map(|data| (data.name, data.value))
+            .collect();
+
+        for (key, elements, eval_context, generator) in restored {
+            if let Some(ctx) = self.contexts.get_mut(&key) {
+                ctx.elements = elements;
+                ctx.eval_context = eval_context;
+                ctx.generator = generator;
+            }
+        }
+        self.status = snapshot.status;
+        self.current_pos = snapshot.current_position;
+        self.pending_jump = snapshot.jump;
+        self.leaving_vertex = snapshot.leaving_vertex;
+        self.global_data = global_data;
+        self.start_pos = snapshot.start_position;
+        self.start_override = snapshot.start_override;
+        self.profile = snapshot.profile;
+        self.profile.start();
+        self.rng.seed(snapshot.rng_state);
+        log::debug!("The machine was restored at: {:?}", self.current_pos);
+        Ok(())
+    }
+
+    /*
+     * Returns an iterator over the rest of the steps of a running walk, without resetting
+     * the machine. Used to continue a walk after a restore.
+     */
+    pub fn resume(&mut self) -> Walk<'_> {
+        Walk {
+            machine: self,
+            started: true,
+        }
+    }
+}
+
+/*
+ * A walk through the models of a machine, one step at a time.
+ */
+pub struct Walk<'a> {
+    machine: &'a mut Machine,
+    started: bool,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = Result<Step, MachineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            if let Err(err) = self.machine.reset() {
+                self.machine.record_failure(&err);
+                return Some(Err(err.into()));
+            }
+        }
+
+        if self.machine.status != MachineStatus::Running {
+            return None;
+        }
+
+        if self.machine.is_all_fullfilled() {
+            log::debug!("All models are fullfilled and the machine is running");
+            self.machine.status = MachineStatus::Ended;
+            self.machine.profile.close();
+            log::debug!("The machine has ended");
+            return None;
+        }
+
+        match self.machine.step() {
+            Ok(step) => Some(Ok(step)),
+            Err(err) => {
+                if self.machine.status != MachineStatus::Failed {
+                    self.machine.record_failure(&err);
+                }
+                log::debug!("The machine has failed");
+                Some(Err(err.into()))
+            }
+        }
     }
 }
@@ -0,0 +1,338 @@
+// Parsers of the generators, and their stop conditions, as written in the models.
+// For example: `random(edge_coverage(100) and vertex_coverage(100))`
+// For parsing nom is used. Some relevant articles:
+//  - https://blog.adamchalmers.com/nom-chars/
+use crate::generator::{Generator, PredefinedPathGenerator, RandomGenerator};
+use crate::stop_condition::{
+    Condition, DependencyEdgeCoverage, EdgeCoverage, Length, PredefinedPath, ReachedEdge,
+    ReachedVertex, RequirementCoverage, TimeDuration, VertexCoverage,
+};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while1},
+    character::complete::{alpha1, alphanumeric1, multispace0},
+    combinator::{map_res, recognize},
+    multi::{many0_count, separated_list1},
+    sequence::{delimited, pair, preceded},
+    IResult,
+};
+use parse_hyperlinks::take_until_unbalanced;
+
+pub fn identifier(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        alt((alpha1, tag("_"))),
+        many0_count(alt((alphanumeric1, tag("_")))),
+    ))(input)
+}
+
+fn argument(input: &str) -> IResult<&str, &str> {
+    delimited(
+        tag("("),
+        delimited(
+            multispace0,
+            take_while1(|c: char| c != '(' && c != ')' && !c.is_whitespace()),
+            multispace0,
+        ),
+        tag(")"),
+    )(input)
+}
+
+fn percent(arg: &str) -> Result<f32, String> {
+    match arg.parse::<f32>() {
+        Ok(p) if (0f32..=100f32).contains(&p) => Ok(p / 100f32),
+        _ => Err(format!(
+            "Expected a percentage between 0 and 100, got: {}",
+            arg
+        )),
+    }
+}
+
+fn number(arg: &str) -> Result<u64, String> {
+    arg.parse::<u64>()
+        .map_err(|err| format!("Expected a number, got: {}, {:?}", arg, err))
+}
+
+fn single(input: &str) -> IResult<&str, Condition> {
+    map_res(
+        pair(identifier, argument),
+        |(name, arg)| -> Result<Condition, String> {
+            match name {
+                "edge_coverage" => Ok(Condition::EdgeCoverage(EdgeCoverage::new(percent(arg)?))),
+                "vertex_coverage" => Ok(Condition::VertexCoverage(VertexCoverage::new(percent(
+                    arg,
+                )?))),
+                "requirement_coverage" => Ok(Condition::RequirementCoverage(
+                    RequirementCoverage::new(percent(arg)?),
+                )),
+                "dependency_edge_coverage" => match number(arg)? {
+                    dependency @ 0..=100 => Ok(Condition::DependencyEdgeCoverage(
+                        DependencyEdgeCoverage::new(dependency as u32),
+                    )),
+                    _ => Err(format!(
+                        "Expected a dependency between 0 and 100, got: {}",
+                        arg
+                    )),
+                },
+                "reached_vertex" => Ok(Condition::ReachedVertex(ReachedVertex::new(
+                    arg.to_string(),
+                ))),
+                "reached_edge" => Ok(Condition::ReachedEdge(ReachedEdge::new(arg.to_string()))),
+                "length" => Ok(Condition::Length(Length::new(number(arg)?))),
+                "time_duration" => Ok(Condition::TimeDuration(TimeDuration::new(number(arg)?))),
+                _ => Err(format!("Unknown stop condition: {}", name)),
+            }
+        },
+    )(input)
+}
+
+fn keyword(input: &str) -> IResult<&str, Condition> {
+    map_res(identifier, |name| match name {
+        "predefined_path" => Ok(Condition::PredefinedPath(PredefinedPath::new())),
+        _ => Err(format!("Unknown stop condition: {}", name)),
+    })(input)
+}
+
+fn factor(input: &str) -> IResult<&str, Condition> {
+    preceded(
+        multispace0,
+        alt((
+            delimited(tag("("), expression, preceded(multispace0, tag(")"))),
+            single,
+            keyword,
+        )),
+    )(input)
+}
+
+fn term(input: &str) -> IResult<&str, Condition> {
+    let and = delimited(
+        multispace0,
+        alt((tag_no_case("and"), tag("&&"))),
+        multispace0,
+    );
+    let (rest, mut conditions) = separated_list1(and, factor)(input)?;
+    if conditions.len() == 1 {
+        return Ok((rest, conditions.remove(0)));
+    }
+    Ok((rest, Condition::And(conditions)))
+}
+
+pub fn expression(input: &str) -> IResult<&str, Condition> {
+    let or = delimited(
+        multispace0,
+        alt((tag_no_case("or"), tag("||"))),
+        multispace0,
+    );
+    let (rest, mut conditions) = separated_list1(or, term)(input)?;
+    if conditions.len() == 1 {
+        return Ok((rest, conditions.remove(0)));
+    }
+    Ok((rest, Condition::Or(conditions)))
+}
+
+/*
+ * Parses a stop condition expression, for example: `edge_coverage(100) and reached_vertex(v1)`
+ */
+pub fn stop_condition(input: &str) -> Result<Condition, String> {
+    match expression(input) {
+        Ok((rest, condition)) if rest.trim().is_empty() => Ok(condition),
+        Ok((rest, _)) => Err(format!(
+            "Could not parse the stop condition: {:?}, unexpected: {:?}",
+            input, rest
+        )),
+        Err(err) => Err(format!(
+            "Could not parse the stop condition: {:?}, {:?}",
+            input, err
+        )),
+    }
+}
+
+/*
+ * Splits a generator into its name, and everything inside its parentheses.
+ */
+fn name_and_conditions(input: &str) -> IResult<&str, (&str, &str)> {
+    let (rest, name) = delimited(multispace0, identifier, multispace0)(input)?;
+    let (rest, inner) = delimited(tag("("), take_until_unbalanced('(', ')'), tag(")"))(rest)?;
+    Ok((rest, (name, inner)))
+}
+
+/*
+ * Parses a generator, for example: `random(edge_coverage(100))`
+ * The edges of a predefined path are not part of the generator string, and are added from
+ * the model. The quick_random and weighted_random generators are not implemented, and walk
+ * as random.
+ */
+pub fn generator(input: &str) -> Result<Generator, String> {
+    let (name, inner) = match name_and_conditions(input) {
+        Ok((rest, result)) if rest.trim().is_empty() => result,
+        _ => {
+            let msg = format!("Could not parse the generator: {:?}", input);
+            log::error!("{}", msg);
+            return Err(msg);
+        }
+    };
+
+    let condition = stop_condition(inner)?;
+    match name {
+        "random" => Ok(Generator::Random(
+            RandomGenerator::new().stop_condition(condition),
+        )),
+        "quick_random" | "weighted_random" => {
+            log::warn!(
+                "The generator {} is not implemented, will use random instead",
+                name
+            );
+            Ok(Generator::Random(
+                RandomGenerator::new().stop_condition(condition),
+            ))
+        }
+        "predefined_path" => Ok(Generator::PredefinedPath(
+            PredefinedPathGenerator::new(vec![]).stop_condition(condition),
+        )),
+        _ => {
+            let msg = format!("The generator {} is not supported", name);
+            log::error!("{}", msg);
+            Err(msg)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::GeneratorType;
+    use nom::error::ErrorKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn stop_condition_test() {
+        assert_eq!(
+            stop_condition("edge_coverage(100)"),
+            Ok(Condition::EdgeCoverage(EdgeCoverage::new(1f32)))
+        );
+        assert_eq!(
+            stop_condition("requirement_coverage( 50 )"),
+            Ok(Condition::RequirementCoverage(RequirementCoverage::new(
+                0.5f32
+            )))
+        );
+        assert_eq!(
+            stop_condition("edge_coverage(100) OR reached_vertex(v1) and length(10)"),
+            Ok(Condition::Or(vec![
+                Condition::EdgeCoverage(EdgeCoverage::new(1f32)),
+                Condition::And(vec![
+                    Condition::ReachedVertex(ReachedVertex::new("v1".to_string())),
+                    Condition::Length(Length::new(10)),
+                ]),
+            ]))
+        );
+        assert_eq!(
+            stop_condition("(edge_coverage(100) || reached_edge(e_Close)) && time_duration(60)"),
+            Ok(Condition::And(vec![
+                Condition::Or(vec![
+                    Condition::EdgeCoverage(EdgeCoverage::new(1f32)),
+                    Condition::ReachedEdge(ReachedEdge::new("e_Close".to_string())),
+                ]),
+                Condition::TimeDuration(TimeDuration::new(60)),
+            ]))
+        );
+        assert_eq!(
+            stop_condition("dependency_edge_coverage(80)"),
+            Ok(Condition::DependencyEdgeCoverage(
+                DependencyEdgeCoverage::new(80)
+            ))
+        );
+
+        // Negative tests
+        assert!(stop_condition("edge_coverage(101)").is_err());
+        assert!(stop_condition("edge_coverage(100) OR").is_err());
+        assert!(stop_condition("no_such_condition(1)").is_err());
+        assert!(stop_condition("length(ten)").is_err());
+        assert!(stop_condition("dependency_edge_coverage(101)").is_err());
+    }
+
+    #[test]
+    fn name_and_conditions_test() {
+        assert_eq!(
+            name_and_conditions("quick_random(edge_coverage(100))"),
+            Ok(("", ("quick_random", "edge_coverage(100)")))
+        );
+        assert_eq!(
+            name_and_conditions("random(edge_coverage(100))"),
+            Ok(("", ("random", "edge_coverage(100)")))
+        );
+        assert_eq!(
+            name_and_conditions("random(edge_coverage(100) OR reached_vertex(v1))"),
+            Ok(("", ("random", "edge_coverage(100) OR reached_vertex(v1)")))
+        );
+        assert_eq!(
+            name_and_conditions("random(edge_coverage(100) OR (reached_vertex(v1) AND time(800)))"),
+            Ok((
+                "",
+                (
+                    "random",
+                    "edge_coverage(100) OR (reached_vertex(v1) AND time(800))"
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn generator_test() {
+        assert_eq!(
+            generator("random(edge_coverage(100))"),
+            Ok(Generator::Random(RandomGenerator::new().stop_condition(
+                Condition::EdgeCoverage(EdgeCoverage::new(1f32))
+            )))
+        );
+        for (generator_str, generator_type) in [
+            ("random(length(5))", "RandomGenerator"),
+            (
+                "quick_random(edge_coverage(100) or length(5))",
+                "RandomGenerator",
+            ),
+            ("weighted_random(length(5))", "RandomGenerator"),
+            (
+                "predefined_path(predefined_path)",
+                "PredefinedPathGenerator",
+            ),
+        ] {
+            let generator = generator(generator_str).expect("Expected a generator");
+            assert_eq!(generator.generator_type(), generator_type);
+        }
+
+        // Negative tests
+        assert!(generator("a_star(reached_vertex(v1))").is_err());
+        assert!(generator("no_such_generator(edge_coverage(100))").is_err());
+        assert!(generator("random(edge_coverage(100)").is_err());
+        assert!(generator("random(edge_coverage(100)) x").is_err());
+    }
+
+    #[test]
+    fn take_until_unbalanced_test() {
+        assert_eq!(
+            take_until_unbalanced('(', ')')("edge_coverage(100))"),
+            Ok((")", "edge_coverage(100)"))
+        );
+        assert_eq!(
+            take_until_unbalanced('(', ')')("edge_coverage(100) or vertex_reached(v1))"),
+            Ok((")", "edge_coverage(100) or vertex_reached(v1)"))
+        );
+        assert_eq!(
+            take_until_unbalanced('(', ')')("100) or vertex_reached(v1))"),
+            Ok((") or vertex_reached(v1))", "100"))
+        );
+        assert_eq!(take_until_unbalanced('(', ')')("v1))"), Ok(("))", "v1")));
+        assert_eq!(take_until_unbalanced('(', ')')("abc"), Ok(("", "abc")));
+        assert_eq!(
+            take_until_unbalanced('(', ')')("u(())r()l)abc"),
+            Ok((")abc", "u(())r()l"))
+        );
+        assert_eq!(
+            take_until_unbalanced('(', ')')("u(())r(labc"),
+            Err(nom::Err::Error(nom::error::Error::new(
+                "u(())r(labc",
+                ErrorKind::TakeUntil
+            )))
+        );
+    }
+}
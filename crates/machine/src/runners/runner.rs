@@ -0,0 +1,132 @@
+use crate::{Data, Machine, Step};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub type Handler<'a> = Box<dyn FnMut(&mut HandlerContext) -> Result<(), String> + 'a>;
+
+/*
+ * What a handler gets to work with: the current step, and the data of its model.
+ */
+pub struct HandlerContext<'a> {
+    step: &'a Step,
+    machine: &'a mut Machine,
+}
+
+impl<'a> HandlerContext<'a> {
+    pub fn step(&self) -> &Step {
+        self.step
+    }
+
+    pub fn get_data(&self) -> Result<Vec<Data>, String> {
+        self.machine.get_data(&self.step.position.model_id)
+    }
+
+    pub fn get_global_data(&self) -> Vec<Data> {
+        self.machine.get_global_data()
+    }
+
+    pub fn set_data(&mut self, script: &str) -> Result<(), String> {
+        self.machine.set_data(&self.step.position.model_id, script)
+    }
+}
+
+/*
+ * Drives a machine and calls the handler registered for the name of each element, the same
+ * way GraphWalker calls the methods of a test class. A handler returning an error fails the
+ * machine at that step.
+ */
+pub struct Runner<'a> {
+    machine: &'a mut Machine,
+    handlers: BTreeMap<String, Handler<'a>>,
+    allow_missing: bool,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(machine: &'a mut Machine) -> Self {
+        Self {
+            machine,
+            handlers: BTreeMap::new(),
+            allow_missing: false,
+        }
+    }
+
+    /*
+     * Registers the handler for all elements named `name`.
+     */
+    pub fn on<F>(mut self, name: &str, handler: F) -> Self
+    where
+        F: FnMut(&mut HandlerContext) -> Result<(), String> + 'a,
+    {
+        self.handlers.insert(name.to_string(), Box::new(handler));
+        self
+    }
+
+    /*
+     * When set, elements without a handler are skipped, instead of failing the machine.
+     */
+    pub fn allow_missing(mut self, allow_missing: bool) -> Self {
+        self.allow_missing = allow_missing;
+        self
+    }
+
+    /*
+     * Returns the names of the elements in the models that have no handler registered.
+     */
+    pub fn missing_handlers(&self) -> BTreeSet<String> {
+        let mut missing = BTreeSet::new();
+        for ctx in self.machine.contexts.values() {
+            let edges = ctx.model.edges.values().map(|e| &e.name);
+            let vertices = ctx.model.vertices.values().map(|v| &v.name);
+            for name in edges.chain(vertices).flatten() {
+                if !self.handlers.contains_key(name) {
+                    missing.insert(name.clone());
+                }
+            }
+        }
+        missing
+    }
+
+    /*
+     * Walks the models, calling the handlers of each step, until all models are fullfilled.
+     */
+    pub fn run(mut self) -> Result<(), String> {
+        let missing = self.missing_handlers();
+        if !missing.is_empty() {
+            log::warn!("No handlers are registered for: {:?}", missing);
+        }
+
+        let mut walk = self.machine.steps();
+        while let Some(res) = walk.next() {
+            let step = res.map_err(|err| err.reason)?;
+            if step.element_name.is_empty() {
+                log::debug!("Skipping the unnamed element: {:?}", step.position);
+                continue;
+            }
+
+            let res = match self.handlers.get_mut(&step.element_name) {
+                Some(handler) => {
+                    log::debug!("Calling the handler for: {}", step.element_name);
+                    let mut ctx = HandlerContext {
+                        step: &step,
+                        machine: walk.machine,
+                    };
+                    handler(&mut ctx)
+                }
+                None if self.allow_missing => {
+                    log::debug!("No handler for: {}", step.element_name);
+                    Ok(())
+                }
+                None => Err(format!(
+                    "No handler is registered for: {}",
+                    step.element_name
+                )),
+            };
+
+            if let Err(err) = res {
+                log::error!("{}", err);
+                walk.machine.fail(&err)?;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
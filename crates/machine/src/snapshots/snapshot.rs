@@ -0,0 +1,80 @@
+use crate::generator::Generator;
+use crate::{Context, Data, Jump, Machine, MachineStatus, Position, Profile};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/*
+ * The state of a model in a snapshot: how many times each element has been visited, the
+ * variables of its data context, and the state of its generator.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelSnapshot {
+    pub visits: BTreeMap<String, u32>,
+    pub data: Vec<Data>,
+    pub generator: Generator,
+}
+
+impl ModelSnapshot {
+    fn new(ctx: &Context) -> Self {
+        Self {
+            visits: ctx
+                .elements
+                .iter_visits()
+                .filter(|(_, count)| *count > 0)
+                .map(|(id, count)| (id.clone(), count))
+                .collect(),
+            data: Machine::data_of(ctx),
+            generator: ctx.generator.clone(),
+        }
+    }
+}
+
+/*
+ * Everything needed to resume a walk where it was left, in a machine that has loaded the
+ * same models. The models themselves are not part of the snapshot.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snapshot {
+    pub status: MachineStatus,
+    pub current_position: Position,
+    /// Set when the current position was reached by a jump, that is not yet stepped into.
+    #[serde(default)]
+    pub jump: Option<Jump>,
+    /// Set when the current position is a vertex already stepped into, that is left by the
+    /// next step.
+    #[serde(default)]
+    pub leaving_vertex: bool,
+    pub start_position: Position,
+    /// The start position set with Machine::set_start, used instead of the start element of
+    /// the models when the machine is reset.
+    #[serde(default)]
+    pub start_override: Option<Position>,
+    /// The state of the random number generator of the machine, as given by Rng::get_seed.
+    pub rng_state: u64,
+    pub models: BTreeMap<String, ModelSnapshot>,
+    /// The variables shared by all models.
+    #[serde(default)]
+    pub global_data: Vec<Data>,
+    pub profile: Profile,
+}
+
+impl Snapshot {
+    pub fn new(machine: &Machine) -> Self {
+        Self {
+            status: machine.status.clone(),
+            current_position: machine.current_pos.clone(),
+            jump: machine.pending_jump.clone(),
+            leaving_vertex: machine.leaving_vertex,
+            start_position: machine.start_pos.clone(),
+            start_override: machine.start_override.clone(),
+            rng_state: machine.rng.get_seed(),
+            models: machine
+                .contexts
+                .iter()
+                .map(|(key, ctx)| (key.clone(), ModelSnapshot::new(ctx)))
+                .collect(),
+            global_data: machine.get_global_data(),
+            profile: machine.profile.clone(),
+        }
+    }
+}
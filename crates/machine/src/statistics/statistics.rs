@@ -0,0 +1,261 @@
+use crate::stop_condition::IsFullfilled;
+use crate::{Context, Failure, Machine, MachineStatus, Position};
+use serde_derive::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Serialize, Clone, Default, Debug, PartialEq)]
+pub struct Coverage {
+    pub total: usize,
+    pub visited: usize,
+    pub percent: f32,
+}
+
+impl Coverage {
+    fn new(total: usize, visited: usize) -> Self {
+        let percent = if total == 0 {
+            100f32
+        } else {
+            100f32 * visited as f32 / total as f32
+        };
+        Self {
+            total,
+            visited,
+            percent,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct ModelStatistics {
+    pub model_id: String,
+    pub model_name: String,
+    /// True when the stop conditions of the model are fullfilled.
+    pub fullfilled: bool,
+    pub edge_coverage: Coverage,
+    pub vertex_coverage: Coverage,
+    pub requirement_coverage: Coverage,
+    pub unvisited_edges: Vec<String>,
+    pub unvisited_vertices: Vec<String>,
+    pub unvisited_requirements: Vec<String>,
+    pub visits: BTreeMap<String, u32>,
+    /// Jumps through shared states, from a vertex in this model.
+    pub jumps_out: usize,
+    /// Jumps through shared states, to a vertex in this model.
+    pub jumps_in: usize,
+}
+
+impl ModelStatistics {
+    fn new(ctx: &Context) -> Self {
+        let visits_of = |id: &String| ctx.elements.visits(id);
+
+        let unvisited_edges: Vec<String> = ctx
+            .model
+            .edges
+            .keys()
+            .filter(|id| visits_of(id) == 0)
+            .cloned()
+            .collect();
+        let unvisited_vertices: Vec<String> = ctx
+            .model
+            .vertices
+            .keys()
+            .filter(|id| visits_of(id) == 0)
+            .cloned()
+            .collect();
+
+        let (requirements, visited_requirements) = requirements_of(ctx);
+        let unvisited_requirements: Vec<String> = requirements
+            .difference(&visited_requirements)
+            .cloned()
+            .collect();
+
+        Self {
+            model_id: ctx.id.clone(),
+            model_name: ctx.model.name.clone().unwrap_or_default(),
+            fullfilled: ctx.generator.is_fullfilled(),
+            edge_coverage: Coverage::new(
+                ctx.model.edges.len(),
+                ctx.model.edges.len() - unvisited_edges.len(),
+            ),
+            vertex_coverage: Coverage::new(
+                ctx.model.vertices.len(),
+                ctx.model.vertices.len() - unvisited_vertices.len(),
+            ),
+            requirement_coverage: Coverage::new(requirements.len(), visited_requirements.len()),
+            unvisited_edges,
+            unvisited_vertices,
+            unvisited_requirements,
+            visits: ctx
+                .elements
+                .iter_visits()
+                .map(|(id, visits)| (id.clone(), visits))
+                .collect(),
+            jumps_out: 0,
+            jumps_in: 0,
+        }
+    }
+}
+
+/*
+ * Returns all requirements of a model, and the requirements of the elements that has been visited.
+ */
+fn requirements_of(ctx: &Context) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut requirements = BTreeSet::new();
+    let mut visited = BTreeSet::new();
+
+    let edges = ctx.model.edges.iter().map(|(id, e)| (id, &e.requirements));
+    let vertices = ctx
+        .model
+        .vertices
+        .iter()
+        .map(|(id, v)| (id, &v.requirements));
+
+    for (id, reqs) in edges.chain(vertices) {
+        requirements.extend(reqs.iter().cloned());
+        if ctx.elements.visits(id) > 0 {
+            visited.extend(reqs.iter().cloned());
+        }
+    }
+    (requirements, visited)
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub enum RequirementStatus {
+    Passed,
+    Failed,
+    NotReached,
+}
+
+/*
+ * The time a step took, from when it was returned until the next step was asked for.
+ */
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StepDuration {
+    pub position: Position,
+    pub element_name: String,
+    pub duration_ms: f64,
+}
+
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct Statistics {
+    pub status: MachineStatus,
+    pub failure: Option<Failure>,
+    pub total_steps: usize,
+    pub duration_ms: u64,
+    pub step_duration_ms: f64,
+    /// The durations of the steps, in the order they were taken. The last step has no
+    /// duration until the walk has ended.
+    pub step_durations: Vec<StepDuration>,
+    pub edge_coverage: Coverage,
+    pub vertex_coverage: Coverage,
+    pub requirement_coverage: Coverage,
+    pub requirements: BTreeMap<String, RequirementStatus>,
+    pub shared_state_jumps: usize,
+    pub models: BTreeMap<String, ModelStatistics>,
+}
+
+impl Statistics {
+    pub fn new(machine: &Machine) -> Self {
+        let mut models = BTreeMap::new();
+        let mut requirements = BTreeSet::new();
+        let mut visited_requirements = BTreeSet::new();
+
+        for (key, ctx) in &machine.contexts {
+            let (reqs, visited) = requirements_of(ctx);
+            requirements.extend(reqs);
+            visited_requirements.extend(visited);
+            models.insert(key.clone(), ModelStatistics::new(ctx));
+        }
+
+        let mut shared_state_jumps = 0;
+        for step in machine.profile.jumps() {
+            shared_state_jumps += 1;
+            if let Some(jump) = &step.jump {
+                if let Some(model) = models.get_mut(&jump.from.model_id) {
+                    model.jumps_out += 1;
+                }
+            }
+            if let Some(model) = models.get_mut(&step.position.model_id) {
+                model.jumps_in += 1;
+            }
+        }
+
+        let edges = models.values().map(|m| &m.edge_coverage);
+        let edge_coverage = Coverage::new(
+            edges.clone().map(|c| c.total).sum(),
+            edges.map(|c| c.visited).sum(),
+        );
+        let vertices = models.values().map(|m| &m.vertex_coverage);
+        let vertex_coverage = Coverage::new(
+            vertices.clone().map(|c| c.total).sum(),
+            vertices.map(|c| c.visited).sum(),
+        );
+
+        // Requirements on the failing step have failed, even if they were visited earlier
+        let failed_requirements: BTreeSet<String> = match &machine.profile.failure {
+            Some(Failure {
+                step: Some(step), ..
+            }) => step.requirements.iter().cloned().collect(),
+            _ => BTreeSet::new(),
+        };
+        let mut requirement_status = BTreeMap::new();
+        for req in &requirements {
+            let status = if failed_requirements.contains(req) {
+                RequirementStatus::Failed
+            } else if visited_requirements.contains(req) {
+                RequirementStatus::Passed
+            } else {
+                RequirementStatus::NotReached
+            };
+            requirement_status.insert(req.clone(), status);
+        }
+
+        let total_steps = machine.profile.steps.len();
+        let duration = machine.profile.duration();
+        let step_duration_ms = if total_steps == 0 {
+            0f64
+        } else {
+            duration.as_secs_f64() * 1000f64 / total_steps as f64
+        };
+        let step_durations = machine
+            .profile
+            .steps
+            .iter()
+            .filter_map(|step| {
+                step.duration_ms.map(|duration_ms| StepDuration {
+                    position: step.position.clone(),
+                    element_name: step.element_name.clone(),
+                    duration_ms,
+                })
+            })
+            .collect();
+
+        Self {
+            status: machine.status.clone(),
+            failure: machine.profile.failure.clone(),
+            total_steps,
+            duration_ms: duration.as_millis() as u64,
+            step_duration_ms,
+            step_durations,
+            edge_coverage,
+            vertex_coverage,
+            requirement_coverage: Coverage::new(requirements.len(), visited_requirements.len()),
+            requirements: requirement_status,
+            shared_state_jumps,
+            models,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn coverage() {
+        assert_eq!(Coverage::new(4, 1).percent, 25f32);
+        assert_eq!(Coverage::new(4, 4).percent, 100f32);
+        assert_eq!(Coverage::new(0, 0).percent, 100f32);
+    }
+}
@@ -1,11 +1,31 @@
+use crate::Context;
+use serde_derive::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
 pub trait IsFullfilled {
     fn is_fullfilled(&self) -> bool;
 }
 
 pub trait StopCondition {
     fn condition_type(&self) -> &str;
+
+    /*
+     * Recalculates the fullfilment of the condition from the state of the context.
+     */
+    fn update(&mut self, ctx: &Context);
+}
+
+/*
+ * Returns the ratio of `visited` to `total`, where nothing to visit counts as all visited.
+ */
+fn ratio(visited: usize, total: usize) -> f32 {
+    if total == 0 {
+        return 1f32;
+    }
+    visited as f32 / total as f32
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct EdgeCoverage {
     coverage: f32,
     fullfilment: f32,
@@ -15,6 +35,10 @@ impl StopCondition for EdgeCoverage {
     fn condition_type(&self) -> &str {
         "EdgeCoverage"
     }
+
+    fn update(&mut self, ctx: &Context) {
+        self.fullfilment = ratio(ctx.elements.visited_edges(), ctx.elements.edge_count());
+    }
 }
 
 impl IsFullfilled for EdgeCoverage {
@@ -32,6 +56,7 @@ impl EdgeCoverage {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct VertexCoverage {
     coverage: f32,
     fullfilment: f32,
@@ -41,6 +66,10 @@ impl StopCondition for VertexCoverage {
     fn condition_type(&self) -> &str {
         "VertexCoverage"
     }
+
+    fn update(&mut self, ctx: &Context) {
+        self.fullfilment = ratio(ctx.elements.visited_vertices(), ctx.elements.vertex_count());
+    }
 }
 
 impl IsFullfilled for VertexCoverage {
@@ -58,9 +87,349 @@ impl VertexCoverage {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RequirementCoverage {
+    coverage: f32,
+    fullfilment: f32,
+}
+
+impl StopCondition for RequirementCoverage {
+    fn condition_type(&self) -> &str {
+        "RequirementCoverage"
+    }
+
+    fn update(&mut self, ctx: &Context) {
+        self.fullfilment = ratio(
+            ctx.elements.visited_requirements(),
+            ctx.elements.requirement_count(),
+        );
+    }
+}
+
+impl IsFullfilled for RequirementCoverage {
+    fn is_fullfilled(&self) -> bool {
+        self.fullfilment >= self.coverage
+    }
+}
+
+impl RequirementCoverage {
+    pub fn new(coverage: f32) -> Self {
+        Self {
+            coverage,
+            fullfilment: 0f32,
+        }
+    }
+}
+
+/*
+ * Fullfilled when all edges with a dependency at or above the threshold have been visited.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DependencyEdgeCoverage {
+    dependency: u32,
+    fullfilment: f32,
+}
+
+impl StopCondition for DependencyEdgeCoverage {
+    fn condition_type(&self) -> &str {
+        "DependencyEdgeCoverage"
+    }
+
+    fn update(&mut self, ctx: &Context) {
+        let (total, visited) = ctx.elements.dependency_edges(self.dependency);
+        self.fullfilment = ratio(visited, total);
+    }
+}
+
+impl IsFullfilled for DependencyEdgeCoverage {
+    fn is_fullfilled(&self) -> bool {
+        self.fullfilment >= 1f32
+    }
+}
+
+impl DependencyEdgeCoverage {
+    pub fn new(dependency: u32) -> Self {
+        Self {
+            dependency,
+            fullfilment: 0f32,
+        }
+    }
+}
+
+/*
+ * Fullfilled when a vertex with the given name, or id, has been visited.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReachedVertex {
+    name: String,
+    reached: bool,
+}
+
+impl StopCondition for ReachedVertex {
+    fn condition_type(&self) -> &str {
+        "ReachedVertex"
+    }
+
+    fn update(&mut self, ctx: &Context) {
+        let elements = &ctx.elements;
+        let by_id = elements.handle(&self.name);
+        let by_name = elements.handles_by_name(&self.name).iter().copied();
+        self.reached = by_id
+            .into_iter()
+            .chain(by_name)
+            .any(|handle| !elements.is_edge(handle) && elements.is_visited(handle));
+    }
+}
+
+impl IsFullfilled for ReachedVertex {
+    fn is_fullfilled(&self) -> bool {
+        self.reached
+    }
+}
+
+impl ReachedVertex {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            reached: false,
+        }
+    }
+}
+
+/*
+ * Fullfilled when an edge with the given name, or id, has been visited.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReachedEdge {
+    name: String,
+    reached: bool,
+}
+
+impl StopCondition for ReachedEdge {
+    fn condition_type(&self) -> &str {
+        "ReachedEdge"
+    }
+
+    fn update(&mut self, ctx: &Context) {
+        let elements = &ctx.elements;
+        let by_id = elements.handle(&self.name);
+        let by_name = elements.handles_by_name(&self.name).iter().copied();
+        self.reached = by_id
+            .into_iter()
+            .chain(by_name)
+            .any(|handle| elements.is_edge(handle) && elements.is_visited(handle));
+    }
+}
+
+impl IsFullfilled for ReachedEdge {
+    fn is_fullfilled(&self) -> bool {
+        self.reached
+    }
+}
+
+impl ReachedEdge {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            reached: false,
+        }
+    }
+}
+
+/*
+ * Fullfilled when the number of steps taken in the model reaches the length.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Length {
+    length: u64,
+    steps: u64,
+}
+
+impl StopCondition for Length {
+    fn condition_type(&self) -> &str {
+        "Length"
+    }
+
+    fn update(&mut self, ctx: &Context) {
+        self.steps = ctx.elements.total_visits();
+    }
+}
+
+impl IsFullfilled for Length {
+    fn is_fullfilled(&self) -> bool {
+        self.steps >= self.length
+    }
+}
+
+impl Length {
+    pub fn new(length: u64) -> Self {
+        Self { length, steps: 0 }
+    }
+}
+
+/*
+ * Fullfilled when the given number of seconds has passed since the first update. The time
+ * walked so far is kept in snapshots, so a restored walk goes on where it was saved.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeDuration {
+    duration: Duration,
+    /// The time passed up to the last update.
+    #[serde(default)]
+    elapsed: Duration,
+    #[serde(skip)]
+    last_update: Option<Instant>,
+}
+
+impl StopCondition for TimeDuration {
+    fn condition_type(&self) -> &str {
+        "TimeDuration"
+    }
+
+    fn update(&mut self, _ctx: &Context) {
+        let now = Instant::now();
+        if let Some(last_update) = self.last_update {
+            self.elapsed += now - last_update;
+        }
+        self.last_update = Some(now);
+    }
+}
+
+impl IsFullfilled for TimeDuration {
+    fn is_fullfilled(&self) -> bool {
+        match self.last_update {
+            Some(last_update) => self.elapsed + last_update.elapsed() >= self.duration,
+            None => false,
+        }
+    }
+}
+
+impl TimeDuration {
+    pub fn new(seconds: u64) -> Self {
+        Self {
+            duration: Duration::from_secs(seconds),
+            elapsed: Duration::ZERO,
+            last_update: None,
+        }
+    }
+}
+
+/*
+ * Fullfilled when all edges of the predefined path have been taken. The progress is given
+ * by the predefined path generator.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PredefinedPath {
+    length: usize,
+    taken: usize,
+}
+
+impl StopCondition for PredefinedPath {
+    fn condition_type(&self) -> &str {
+        "PredefinedPath"
+    }
+
+    fn update(&mut self, _ctx: &Context) {}
+}
+
+impl IsFullfilled for PredefinedPath {
+    fn is_fullfilled(&self) -> bool {
+        self.taken >= self.length
+    }
+}
+
+impl PredefinedPath {
+    pub fn new() -> Self {
+        Self {
+            length: 0,
+            taken: 0,
+        }
+    }
+}
+
+/*
+ * A stop condition, or a combination of stop conditions, as written in a generator.
+ * For example: `edge_coverage(100) OR (reached_vertex(v1) AND length(10))`
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Condition {
+    EdgeCoverage(EdgeCoverage),
+    VertexCoverage(VertexCoverage),
+    RequirementCoverage(RequirementCoverage),
+    DependencyEdgeCoverage(DependencyEdgeCoverage),
+    ReachedVertex(ReachedVertex),
+    ReachedEdge(ReachedEdge),
+    Length(Length),
+    TimeDuration(TimeDuration),
+    PredefinedPath(PredefinedPath),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn update(&mut self, ctx: &Context) {
+        match self {
+            Condition::EdgeCoverage(c) => c.update(ctx),
+            Condition::VertexCoverage(c) => c.update(ctx),
+            Condition::RequirementCoverage(c) => c.update(ctx),
+            Condition::DependencyEdgeCoverage(c) => c.update(ctx),
+            Condition::ReachedVertex(c) => c.update(ctx),
+            Condition::ReachedEdge(c) => c.update(ctx),
+            Condition::Length(c) => c.update(ctx),
+            Condition::TimeDuration(c) => c.update(ctx),
+            Condition::PredefinedPath(c) => c.update(ctx),
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                for c in conditions {
+                    c.update(ctx);
+                }
+            }
+        }
+    }
+}
+
+impl Condition {
+    /*
+     * Sets the progress of the predefined path conditions.
+     */
+    pub fn progress(&mut self, taken: usize, length: usize) {
+        match self {
+            Condition::PredefinedPath(c) => {
+                c.taken = taken;
+                c.length = length;
+            }
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                for c in conditions {
+                    c.progress(taken, length);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl IsFullfilled for Condition {
+    fn is_fullfilled(&self) -> bool {
+        match self {
+            Condition::EdgeCoverage(c) => c.is_fullfilled(),
+            Condition::VertexCoverage(c) => c.is_fullfilled(),
+            Condition::RequirementCoverage(c) => c.is_fullfilled(),
+            Condition::DependencyEdgeCoverage(c) => c.is_fullfilled(),
+            Condition::ReachedVertex(c) => c.is_fullfilled(),
+            Condition::ReachedEdge(c) => c.is_fullfilled(),
+            Condition::Length(c) => c.is_fullfilled(),
+            Condition::TimeDuration(c) => c.is_fullfilled(),
+            Condition::PredefinedPath(c) => c.is_fullfilled(),
+            Condition::And(conditions) => conditions.iter().all(|c| c.is_fullfilled()),
+            Condition::Or(conditions) => conditions.iter().any(|c| c.is_fullfilled()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::elements::Elements;
+    use graph::Edge;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -78,6 +447,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn time_duration() {
+        let ctx = Context::new();
+        let mut time_duration = TimeDuration::new(60);
+        assert_eq!(time_duration.condition_type(), "TimeDuration");
+        assert_eq!(time_duration.is_fullfilled(), false);
+        time_duration.update(&ctx);
+        assert_eq!(time_duration.is_fullfilled(), false);
+
+        // A restored timer goes on from the time passed before it was saved
+        time_duration.elapsed = Duration::from_secs(60);
+        let json = serde_json::to_string(&time_duration).expect("Expected json");
+        let mut restored: TimeDuration = serde_json::from_str(&json).expect("Expected json");
+        assert_eq!(restored.is_fullfilled(), false);
+        restored.update(&ctx);
+        assert_eq!(restored.is_fullfilled(), true);
+    }
+
     #[test]
     fn vertex_coverage() {
         let vertex_coverage = VertexCoverage::new(1f32);
@@ -92,4 +479,49 @@ mod tests {
             "Incorrect fullfillment"
         );
     }
+
+    #[test]
+    fn requirement_coverage() {
+        let requirement_coverage = RequirementCoverage::new(0.5f32);
+        assert_eq!(
+            requirement_coverage.condition_type(),
+            "RequirementCoverage",
+            "Incorrect condition type found"
+        );
+        assert_eq!(
+            requirement_coverage.is_fullfilled(),
+            false,
+            "Incorrect fullfillment"
+        );
+    }
+
+    #[test]
+    fn dependency_edge_coverage() {
+        let mut ctx = Context::new();
+        for (id, dependency) in [
+            ("e1", Some(100)),
+            ("e2", Some(80)),
+            ("e3", Some(20)),
+            ("e4", None),
+        ] {
+            let mut edge = Edge::new().id(id.to_string());
+            edge.dependency = dependency;
+            ctx.model.edges.insert(id.to_string(), edge);
+        }
+        ctx.elements = Elements::new(&ctx.model);
+
+        let mut condition = DependencyEdgeCoverage::new(80);
+        assert_eq!(condition.condition_type(), "DependencyEdgeCoverage");
+        condition.update(&ctx);
+        assert_eq!(condition.is_fullfilled(), false);
+
+        ctx.elements.visit(ctx.elements.handle("e1").unwrap());
+        condition.update(&ctx);
+        assert_eq!(condition.is_fullfilled(), false);
+
+        // Edges below the threshold does not count
+        ctx.elements.visit(ctx.elements.handle("e2").unwrap());
+        condition.update(&ctx);
+        assert_eq!(condition.is_fullfilled(), true);
+    }
 }
@@ -0,0 +1,160 @@
+use graph::{Edge, Model, Models, Vertex};
+use std::collections::BTreeMap;
+
+/*
+ * Builds a random, strongly connected model with `vertex_count` vertices and `edge_count`
+ * edges, meant for benchmarks and stress tests. The vertices are first chained into one cycle
+ * in a shuffled order, which makes every vertex reachable from every other, and the remaining
+ * edges are spread at random. The same seed always gives the same model.
+ *
+ * The cycle is also set as the predefined path of the model, and the walk starts at its
+ * first edge.
+ */
+pub fn strongly_connected_model(
+    id: &str,
+    vertex_count: usize,
+    edge_count: usize,
+    seed: u64,
+) -> Result<Model, String> {
+    if vertex_count == 0 || edge_count < vertex_count {
+        let msg = format!(
+            "A strongly connected model needs at least one vertex, and at least as many edges as vertices, got {} vertices and {} edges",
+            vertex_count, edge_count
+        );
+        log::error!("{}", msg);
+        return Err(msg);
+    }
+
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let mut order: Vec<usize> = (0..vertex_count).collect();
+    rng.shuffle(&mut order);
+
+    let mut model = Model::new();
+    model.id = Some(id.to_string());
+    model.name = Some(id.to_string());
+    model.generator = Some("random(edge_coverage(100))".to_string());
+
+    for i in 0..vertex_count {
+        let mut vertex = Vertex::new().id(format!("v{}", i));
+        vertex.name = Some(format!("v_{}", i));
+        model.vertices.insert(format!("v{}", i), vertex);
+    }
+
+    for i in 0..edge_count {
+        let (source, target) = if i < vertex_count {
+            (order[i], order[(i + 1) % vertex_count])
+        } else {
+            (rng.usize(0..vertex_count), rng.usize(0..vertex_count))
+        };
+        let mut edge = Edge::new()
+            .id(format!("e{}", i))
+            .source_vertex_id(format!("v{}", source))
+            .target_vertex_id(format!("v{}", target));
+        edge.name = Some(format!("e_{}", i));
+        model.edges.insert(format!("e{}", i), edge);
+    }
+
+    model.predefined_path_edge_ids = (0..vertex_count).map(|i| format!("e{}", i)).collect();
+    model.start_element_id = Some("e0".to_string());
+    Ok(model)
+}
+
+/*
+ * Wraps a model into a Models, the way they are handed to the machine.
+ */
+pub fn models_of(model: Model) -> Models {
+    let id = model.id.clone().unwrap_or_default();
+    Models {
+        models: BTreeMap::from([(id, model)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Machine, MachineStatus};
+    use pretty_assertions::assert_eq;
+
+    /*
+     * Returns how many vertices can be reached from `start`, following the edges forwards,
+     * or backwards.
+     */
+    fn reachable(model: &Model, start: &str, forwards: bool) -> usize {
+        let mut next_vertices: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for edge in model.edges.values() {
+            let source = edge.source_vertex_id.as_deref().unwrap();
+            let target = edge.target_vertex_id.as_deref().unwrap();
+            let (from, to) = if forwards {
+                (source, target)
+            } else {
+                (target, source)
+            };
+            next_vertices.entry(from).or_default().push(to);
+        }
+
+        let mut seen = std::collections::BTreeSet::from([start]);
+        let mut queue = vec![start];
+        while let Some(vertex_id) = queue.pop() {
+            for &next in next_vertices.get(vertex_id).into_iter().flatten() {
+                if seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    #[test]
+    fn strongly_connected() {
+        let model = strongly_connected_model("m", 50, 120, 1).expect("Expected a model");
+        assert_eq!(model.vertices.len(), 50);
+        assert_eq!(model.edges.len(), 120);
+        assert_eq!(model.predefined_path_edge_ids.len(), 50);
+        assert_eq!(reachable(&model, "v0", true), 50);
+        assert_eq!(reachable(&model, "v0", false), 50);
+
+        // The same seed gives the same model
+        assert_eq!(model, strongly_connected_model("m", 50, 120, 1).unwrap());
+        assert_ne!(model, strongly_connected_model("m", 50, 120, 2).unwrap());
+
+        // Negative tests
+        assert!(strongly_connected_model("m", 0, 0, 1).is_err());
+        assert!(strongly_connected_model("m", 10, 9, 1).is_err());
+    }
+
+    fn synthetic_machine(vertices: usize, edges: usize, generator: &str) -> Machine {
+        let mut model = strongly_connected_model("synthetic", vertices, edges, 1)
+            .expect("Expected a synthetic model");
+        model.generator = Some(generator.to_string());
+
+        let mut machine = Machine::new();
+        machine.seed(1);
+        assert!(machine.load_models(models_of(model)).is_ok());
+        machine
+    }
+
+    #[test]
+    fn synthetic_predefined_path() {
+        let mut machine = synthetic_machine(2_500, 7_500, "predefined_path(predefined_path)");
+        assert!(machine.walk_with(|_| Ok(())).is_ok());
+
+        // The predefined path is a cycle through all vertices, starting and ending with an edge
+        let statistics = machine.statistics();
+        assert_eq!(statistics.total_steps, 4_999);
+        assert_eq!(statistics.edge_coverage.visited, 2_500);
+        assert_eq!(statistics.vertex_coverage.visited, 2_499);
+        assert_eq!(machine.status, MachineStatus::Ended);
+    }
+
+    /*
+     * Walks a model of a million elements. Run it with: cargo test -- --ignored
+     */
+    #[test]
+    #[ignore]
+    fn stress_synthetic_random() {
+        let mut machine = synthetic_machine(250_000, 750_000, "random(length(1000000))");
+        assert!(machine.walk_with(|_| Ok(())).is_ok());
+        assert_eq!(machine.statistics().total_steps, 1_000_000);
+        assert_eq!(machine.status, MachineStatus::Ended);
+    }
+}
@@ -1,7 +1,12 @@
 use assert_json_diff::assert_json_eq;
-use machine::{Machine, MachineStatus};
+use machine::{
+    runner::Runner, snapshot::Snapshot, statistics::RequirementStatus, Jump, Machine,
+    MachineStatus, Position,
+};
 use pretty_assertions::assert_eq;
 use serde_json::json;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 
 fn resource_path(resource: &str) -> std::path::PathBuf {
     let mut path = std::path::PathBuf::new();
@@ -14,6 +19,17 @@ fn resource_path(resource: &str) -> std::path::PathBuf {
     path
 }
 
+fn login_machine() -> Machine {
+    let mut machine = Machine::new();
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("login.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+    machine
+}
+
 #[test]
 fn walk_multiple_model() {
     let mut machine = Machine::new();
@@ -48,31 +64,9 @@ fn walk_multiple_model() {
         "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
         "{\"model_id\":\"m1\",\"element_id\":\"v3\"}",
         "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e1\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v1\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e2\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e1\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v1\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e2\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
-        "{\"model_id\":\"m1\",\"element_id\":\"v3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
         "{\"model_id\":\"m1\",\"element_id\":\"v3\"}",
         "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
         "{\"model_id\":\"m1\",\"element_id\":\"v3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v3\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e1\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v1\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"e2\"}",
-        "{\"model_id\":\"m2\",\"element_id\":\"v2\"}",
-        "{\"model_id\":\"m1\",\"element_id\":\"v3\"}",
         "{\"model_id\":\"m1\",\"element_id\":\"e1\"}",
         "{\"model_id\":\"m1\",\"element_id\":\"v1\"}",
         "{\"model_id\":\"m1\",\"element_id\":\"e2\"}",
@@ -84,7 +78,7 @@ fn walk_multiple_model() {
         .profile
         .steps
         .iter()
-        .map(|p| serde_json::to_string(&p).unwrap())
+        .map(|step| serde_json::to_string(&step.position).unwrap())
         .collect();
 
     assert_json_eq!(expected, actual);
@@ -123,6 +117,11 @@ fn walk_single_model() {
 fn test_seed() {
     let mut machine = Machine::new();
     machine.seed(8739438725484);
+    assert_eq!(machine.snapshot().rng_state, 8739438725484);
+
+    // The machine has a generator of its own, and leaves the global one alone
+    fastrand::seed(8739438725484);
+    machine.seed(1);
     let index = fastrand::i32(0..1000);
     assert_eq!(index, 186);
     let index = fastrand::i32(0..1000);
@@ -193,3 +192,1045 @@ fn test_a_model() {
         Err::<(), Result<(), String>>(res)
     );
 }
+
+#[test]
+fn get_and_set_data() {
+    let mut machine = login_machine();
+    assert!(machine.reset().is_ok());
+
+    let data = machine
+        .get_data("login")
+        .expect("Expected data for the model");
+    let actual: Vec<(&str, &evalexpr::Value)> =
+        data.iter().map(|d| (d.name(), d.value())).collect();
+    assert_eq!(
+        actual,
+        vec![
+            ("rememberMe", &evalexpr::Value::Boolean(false)),
+            ("validLogin", &evalexpr::Value::Boolean(false)),
+        ]
+    );
+
+    assert!(machine.set_data("login", "validLogin = true").is_ok());
+    let data = machine
+        .get_data("login")
+        .expect("Expected data for the model");
+    assert_eq!(data[1].value(), &evalexpr::Value::Boolean(true));
+
+    // Negative tests
+    assert!(machine.get_data("no_such_model").is_err());
+    assert!(machine.set_data("no_such_model", "x = 1").is_err());
+    assert!(machine.set_data("login", "validLogin = ").is_err());
+}
+
+#[test]
+fn set_data_between_steps() {
+    // The guards out of n1 are checked when the step after n1 is asked for, so data set
+    // while at n1 decides between e1 and e7
+    for seed in [1, 1234, 8739438725484] {
+        let mut machine = login_machine();
+        machine.seed(seed);
+        assert!(machine.reset().is_ok());
+        assert_eq!(machine.step().unwrap().position, position("login", "n1"));
+        assert_eq!(machine.current_position(), &position("login", "n1"));
+        assert!(machine
+            .set_data("login", "rememberMe = true; validLogin = true")
+            .is_ok());
+        assert_eq!(machine.step().unwrap().position, position("login", "e7"));
+
+        let mut machine = login_machine();
+        machine.seed(seed);
+        assert!(machine.reset().is_ok());
+        assert_eq!(machine.step().unwrap().position, position("login", "n1"));
+        assert_eq!(machine.step().unwrap().position, position("login", "e1"));
+    }
+}
+
+#[test]
+fn fail() {
+    let mut machine = login_machine();
+
+    // A machine that is not running can not be failed
+    assert!(machine.fail("Not started").is_err());
+
+    assert!(machine.reset().is_ok());
+    assert!(machine.step().is_ok());
+    assert!(machine.step().is_ok());
+    assert!(machine.fail("The system under test crashed").is_ok());
+    assert_eq!(machine.status, MachineStatus::Failed);
+
+    let failure = machine.profile.failure.clone().expect("Expected a failure");
+    assert_eq!(failure.reason, "The system under test crashed");
+    assert_eq!(
+        failure.step.expect("Expected a failed step").position,
+        machine.profile.steps[1].position
+    );
+
+    assert!(machine.step().is_err());
+    assert!(machine.fail("Once more").is_err());
+    assert_eq!(machine.profile.steps.len(), 2);
+}
+
+#[test]
+fn statistics() {
+    let mut machine = login_machine();
+    machine.seed(1234);
+
+    let statistics = machine.statistics();
+    assert_eq!(statistics.total_steps, 0);
+    assert_eq!(statistics.status, MachineStatus::NotStarted);
+
+    assert!(machine.walk().is_ok());
+
+    let statistics = machine.statistics();
+    assert_eq!(statistics.status, MachineStatus::Ended);
+    assert!(statistics.failure.is_none());
+    assert_eq!(statistics.total_steps, machine.profile.steps.len());
+    assert_eq!(statistics.edge_coverage.total, 8);
+    assert_eq!(statistics.edge_coverage.visited, 8);
+    assert_eq!(statistics.edge_coverage.percent, 100f32);
+    assert_eq!(statistics.vertex_coverage.total, 3);
+    assert_eq!(statistics.requirement_coverage.total, 0);
+
+    let model = statistics
+        .models
+        .get("login")
+        .expect("Expected the login model");
+    assert_eq!(model.model_name, "Login");
+    assert!(model.unvisited_edges.is_empty());
+    assert!(model.unvisited_vertices.is_empty());
+    assert_eq!(
+        model.visits.values().sum::<u32>() as usize,
+        statistics.total_steps
+    );
+}
+
+#[test]
+fn step_timings() {
+    let mut machine = login_machine();
+    assert!(machine.reset().is_ok());
+
+    let first = machine.step().expect("Expected a step");
+    assert!(first.timestamp > 0);
+    assert!(first.duration_ms.is_none());
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    let second = machine.step().expect("Expected a step");
+    assert!(second.timestamp >= first.timestamp + 20);
+
+    // The duration of a step is known when the next step is asked for
+    let duration = machine.profile.steps[0]
+        .duration_ms
+        .expect("Expected a duration");
+    assert!(duration >= 20f64);
+    assert!(machine.profile.steps[1].duration_ms.is_none());
+
+    // Failing closes the duration of the last step
+    assert!(machine.fail("Too slow").is_ok());
+    assert!(machine.profile.steps[1].duration_ms.is_some());
+    assert!(machine.profile.duration().as_millis() >= 20);
+
+    let slowest = machine.profile.slowest_steps(1);
+    assert_eq!(slowest.len(), 1);
+    assert_eq!(slowest[0].position, machine.profile.steps[0].position);
+
+    let step_durations = machine.statistics().step_durations;
+    assert_eq!(step_durations.len(), 2);
+    assert_eq!(step_durations[0].position, first.position);
+    assert_eq!(step_durations[0].duration_ms, duration);
+
+    // A reset starts a new profile
+    assert!(machine.reset().is_ok());
+    assert!(machine.profile.steps.is_empty());
+    assert!(machine.profile.failure.is_none());
+    assert!(machine.statistics().step_durations.is_empty());
+}
+
+#[test]
+fn requirements() {
+    let mut machine = Machine::new();
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("requirements.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+
+    let res = machine.walk();
+    assert_eq!(
+        res.is_ok(),
+        true,
+        "{:?}",
+        Err::<(), Result<(), String>>(res)
+    );
+
+    // requirement_coverage(75) is fullfilled when 3 of the 4 requirements are passed
+    let actual: Vec<(&String, &Vec<String>)> = machine
+        .profile
+        .steps
+        .iter()
+        .map(|p| (&p.position.element_id, &p.requirements))
+        .collect();
+    assert_eq!(
+        actual,
+        vec![
+            (&"v1".to_string(), &vec!["REQ-1".to_string()]),
+            (&"e1".to_string(), &vec!["REQ-2".to_string()]),
+            (
+                &"v2".to_string(),
+                &vec!["REQ-1".to_string(), "REQ-3".to_string()]
+            ),
+        ]
+    );
+
+    let statistics = machine.statistics();
+    assert_eq!(statistics.requirement_coverage.total, 4);
+    assert_eq!(statistics.requirement_coverage.visited, 3);
+    assert_eq!(
+        statistics.requirements.get("REQ-1"),
+        Some(&RequirementStatus::Passed)
+    );
+    assert_eq!(
+        statistics.requirements.get("REQ-4"),
+        Some(&RequirementStatus::NotReached)
+    );
+    assert_eq!(
+        statistics.models["req"].unvisited_requirements,
+        vec!["REQ-4".to_string()]
+    );
+}
+
+#[test]
+fn failed_requirements() {
+    let mut machine = Machine::new();
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("requirements.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+    assert!(machine.reset().is_ok());
+    assert!(machine.step().is_ok());
+    assert!(machine.step().is_ok());
+    assert!(machine.fail("Could not open").is_ok());
+
+    let statistics = machine.statistics();
+    assert_eq!(
+        statistics.requirements.get("REQ-1"),
+        Some(&RequirementStatus::Passed)
+    );
+    assert_eq!(
+        statistics.requirements.get("REQ-2"),
+        Some(&RequirementStatus::Failed)
+    );
+    assert_eq!(
+        statistics.requirements.get("REQ-3"),
+        Some(&RequirementStatus::NotReached)
+    );
+}
+
+#[test]
+fn replay() {
+    let mut machine = login_machine();
+    machine.seed(1234);
+    assert!(machine.walk().is_ok());
+
+    let path: Vec<Position> = machine
+        .profile
+        .steps
+        .iter()
+        .map(|s| s.position.clone())
+        .collect();
+
+    let mut replay = login_machine();
+    let mut replayed = Vec::new();
+    let res = replay.replay(path.clone(), |_, step| {
+        replayed.push(step.position.clone());
+        Ok(())
+    });
+    assert_eq!(
+        res.is_ok(),
+        true,
+        "{:?}",
+        Err::<(), Result<(), String>>(res)
+    );
+    assert_eq!(replay.status, MachineStatus::Ended);
+
+    let actual: Vec<Position> = replay
+        .profile
+        .steps
+        .iter()
+        .map(|s| s.position.clone())
+        .collect();
+    assert_eq!(actual, path);
+    assert_eq!(replayed, path);
+}
+
+#[test]
+fn replay_illegal_path() {
+    let mut machine = login_machine();
+
+    let position = |element_id: &str| Position {
+        model_id: "login".to_string(),
+        element_id: element_id.to_string(),
+    };
+
+    // Must start at an element in the models
+    assert!(machine.replay(vec![], |_, _| Ok(())).is_err());
+    assert!(machine
+        .replay(vec![position("no_such_element")], |_, _| Ok(()))
+        .is_err());
+    assert_eq!(machine.status, MachineStatus::Failed);
+
+    // e2 is not an out edge of n1
+    assert!(machine
+        .replay(vec![position("n1"), position("e2")], |_, _| Ok(()))
+        .is_err());
+
+    // The target vertex of e1 is n2
+    assert!(machine
+        .replay(
+            vec![position("n1"), position("e1"), position("n3")],
+            |_, _| { Ok(()) }
+        )
+        .is_err());
+
+    // The guard of e7, rememberMe&&validLogin, does not hold
+    let res = machine.replay(vec![position("n1"), position("e7")], |_, _| Ok(()));
+    assert!(res.unwrap_err().contains("guard"));
+    let failure = machine.profile.failure.clone().expect("Expected a failure");
+    assert!(failure.reason.contains("guard"));
+
+    assert!(machine
+        .replay(
+            vec![position("n1"), position("e1"), position("n2")],
+            |_, _| { Ok(()) }
+        )
+        .is_ok());
+
+    // A failing sink fails the machine at that step
+    let res = machine.replay(vec![position("n1"), position("e1")], |_, step| {
+        match step.position.element_id.as_str() {
+            "e1" => Err("Could not start the client".to_string()),
+            _ => Ok(()),
+        }
+    });
+    assert_eq!(res, Err("Could not start the client".to_string()));
+    assert_eq!(machine.status, MachineStatus::Failed);
+    let failure = machine.profile.failure.clone().expect("Expected a failure");
+    assert_eq!(failure.step.unwrap().position, position("e1"));
+}
+
+#[test]
+fn replay_from_recorded_start() {
+    let mut machine = login_machine();
+    let position = |element_id: &str| Position {
+        model_id: "login".to_string(),
+        element_id: element_id.to_string(),
+    };
+
+    // A path recorded from another start element is replayed from where it starts
+    let path = vec![position("n2"), position("e2"), position("n3")];
+    assert!(machine.replay(path.clone(), |_, _| Ok(())).is_ok());
+    assert_eq!(machine.status, MachineStatus::Ended);
+    assert_eq!(machine.profile.steps.len(), 3);
+
+    // ...without changing where walks start
+    assert!(machine.reset().is_ok());
+    assert_eq!(machine.current_position(), &position("n1"));
+}
+
+#[test]
+fn predefined_path() {
+    let mut machine = Machine::new();
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("predefinedPath.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+
+    assert!(machine.walk().is_ok());
+    assert_eq!(machine.status, MachineStatus::Ended);
+
+    // The walk follows the edges of the predefined path, in order
+    let path: Vec<String> = machine
+        .profile
+        .steps
+        .iter()
+        .map(|s| s.position.element_id.clone())
+        .collect();
+    assert_eq!(
+        path,
+        vec!["n1", "e1", "n2", "e8", "n2", "e5", "n2", "e2", "n3", "e3", "n2", "e6"]
+    );
+}
+
+#[test]
+fn predefined_path_not_connected() {
+    let mut models = io::json_read::read(resource_path("predefinedPath.json").to_str().unwrap())
+        .expect("Expexted the test file to be loaded");
+    // e2 goes from n2 to n3, but the walk starts at n1
+    models
+        .models
+        .get_mut("predefined_path")
+        .unwrap()
+        .predefined_path_edge_ids = vec!["e2".to_string()];
+
+    let mut machine = Machine::new();
+    assert!(machine.load_models(models).is_ok());
+    let res = machine.walk();
+    assert!(res.unwrap_err().contains("is not an out edge of vertex n1"));
+    assert_eq!(machine.status, MachineStatus::Failed);
+}
+
+#[test]
+fn iterate_steps() {
+    let mut machine = login_machine();
+
+    let mut positions = Vec::new();
+    for step in machine.steps() {
+        let step = step.expect("Expected a step");
+        positions.push(step.position);
+    }
+    assert_eq!(machine.status, MachineStatus::Ended);
+    assert_eq!(
+        positions[0],
+        Position {
+            model_id: "login".to_string(),
+            element_id: "n1".to_string(),
+        }
+    );
+    assert_eq!(positions.len(), machine.profile.steps.len());
+
+    // A new walk starts over, with a profile of its own
+    let count = machine.steps().count();
+    assert!(count > 0);
+    assert_eq!(count, machine.profile.steps.len());
+    assert_eq!(machine.status, MachineStatus::Ended);
+    assert_eq!(machine.statistics().edge_coverage.percent, 100f32);
+}
+
+#[test]
+fn walk_with_sink() {
+    let mut machine = login_machine();
+
+    let mut count = 0;
+    let res = machine.walk_with(|step| {
+        count += 1;
+        if step.element_name == "v_Browse" {
+            return Err("Could not browse".to_string());
+        }
+        Ok(())
+    });
+    assert_eq!(res, Err("Could not browse".to_string()));
+    assert_eq!(machine.status, MachineStatus::Failed);
+    assert_eq!(count, machine.profile.steps.len());
+
+    let failure = machine.profile.failure.expect("Expected a failure");
+    assert_eq!(failure.reason, "Could not browse");
+    assert_eq!(failure.step.unwrap().element_name, "v_Browse");
+}
+
+#[test]
+fn runner() {
+    let mut machine = login_machine();
+    let calls: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    let names = [
+        "e_StartClient",
+        "e_ValidPremiumCredentials",
+        "e_Logout",
+        "e_Exit",
+        "e_ToggleRememberMe",
+        "e_Close",
+        "e_InvalidCredentials",
+        "v_ClientNotRunning",
+        "v_LoginPrompted",
+    ];
+
+    let mut runner = Runner::new(&mut machine);
+    assert_eq!(runner.missing_handlers().len(), 10);
+    for name in names {
+        runner = runner.on(name, |ctx| {
+            calls.borrow_mut().push(ctx.step().element_name.clone());
+            Ok(())
+        });
+    }
+    let runner = runner.on("v_Browse", |ctx| {
+        calls.borrow_mut().push(ctx.step().element_name.clone());
+        let data = ctx.get_data()?;
+        assert!(data.iter().any(|d| d.name() == "validLogin"));
+        ctx.set_data("browsed=true")
+    });
+    assert!(runner.missing_handlers().is_empty());
+    assert!(runner.run().is_ok());
+
+    assert_eq!(machine.status, MachineStatus::Ended);
+    let calls = calls.into_inner();
+    assert_eq!(calls.len(), machine.profile.steps.len());
+    assert!(calls.contains(&"v_Browse".to_string()));
+    assert!(machine
+        .get_data("login")
+        .unwrap()
+        .iter()
+        .any(|d| d.name() == "browsed"));
+}
+
+#[test]
+fn runner_handler_fails() {
+    let mut machine = login_machine();
+    let res = Runner::new(&mut machine)
+        .allow_missing(true)
+        .on("v_Browse", |_| Err("The browser crashed".to_string()))
+        .run();
+    assert_eq!(res, Err("The browser crashed".to_string()));
+
+    assert_eq!(machine.status, MachineStatus::Failed);
+    let failure = machine.profile.failure.expect("Expected a failure");
+    assert_eq!(failure.reason, "The browser crashed");
+    assert_eq!(failure.step.unwrap().element_name, "v_Browse");
+}
+
+#[test]
+fn runner_missing_handler() {
+    let mut machine = login_machine();
+    let runner = Runner::new(&mut machine).on("v_ClientNotRunning", |_| Ok(()));
+    assert!(runner.missing_handlers().contains("e_StartClient"));
+    assert!(!runner.missing_handlers().contains("v_ClientNotRunning"));
+
+    let res = runner.run();
+    assert_eq!(
+        res,
+        Err("No handler is registered for: e_StartClient".to_string())
+    );
+    assert_eq!(machine.status, MachineStatus::Failed);
+}
+
+#[test]
+fn start_element() {
+    let mut machine = login_machine();
+
+    let position = machine
+        .find_position("v_Browse")
+        .expect("Expected a position");
+    assert_eq!(
+        position,
+        Position {
+            model_id: "login".to_string(),
+            element_id: "n3".to_string(),
+        }
+    );
+    assert!(machine.set_start(position.clone()).is_ok());
+    assert!(machine.walk().is_ok());
+    assert_eq!(machine.start_pos, position);
+    assert_eq!(machine.profile.steps[0].position, position);
+
+    // Starting at an edge
+    let position = machine.find_position("e4").expect("Expected a position");
+    assert!(machine.set_start(position.clone()).is_ok());
+    assert_eq!(machine.steps().next().unwrap().unwrap().position, position);
+}
+
+#[test]
+fn start_element_invalid() {
+    let mut machine = login_machine();
+
+    // e_StartClient is the name of both e1 and e7
+    let res = machine.find_position("e_StartClient");
+    assert!(res.unwrap_err().contains("matches more than one element"));
+    assert!(machine.find_position("v_Unknown").is_err());
+
+    assert!(machine
+        .set_start(Position {
+            model_id: "login".to_string(),
+            element_id: "x".to_string(),
+        })
+        .is_err());
+    assert!(machine.set_start(Position::default()).is_err());
+}
+
+#[test]
+fn snapshot_restore() {
+    let mut machine = login_machine();
+    machine.seed(8739438725484);
+
+    let mut walk = machine.steps();
+    for _ in 0..20 {
+        assert!(walk.next().unwrap().is_ok());
+    }
+    let json = serde_json::to_string(&machine.snapshot()).expect("Expected a json snapshot");
+
+    let rest_of_walk = |machine: &mut Machine| -> Vec<Position> {
+        machine
+            .resume()
+            .map(|step| step.unwrap().position)
+            .collect()
+    };
+    let expected = rest_of_walk(&mut machine);
+    assert!(!expected.is_empty());
+
+    let mut restored = login_machine();
+    let snapshot: Snapshot = serde_json::from_str(&json).expect("Expected a snapshot");
+    assert!(restored.restore(snapshot).is_ok());
+    assert_eq!(restored.profile.steps.len(), 20);
+    assert_eq!(rest_of_walk(&mut restored), expected);
+    assert_eq!(restored.status, MachineStatus::Ended);
+    assert_eq!(
+        restored.statistics().edge_coverage,
+        machine.statistics().edge_coverage
+    );
+    assert_eq!(
+        json!(restored.get_data("login").unwrap()),
+        json!(machine.get_data("login").unwrap())
+    );
+}
+
+#[test]
+fn seed_per_machine() {
+    let walk = |machine: &mut Machine| -> Vec<Position> {
+        machine.steps().map(|step| step.unwrap().position).collect()
+    };
+    let mut machine = login_machine();
+    machine.seed(147);
+    let expected = walk(&mut machine);
+
+    // Machines walking side by side do not draw from each other's random numbers
+    let mut first = login_machine();
+    let mut second = login_machine();
+    first.seed(147);
+    second.seed(147);
+    let mut first_walk = first.steps();
+    let mut second_walk = second.steps();
+    let mut positions = (Vec::new(), Vec::new());
+    loop {
+        let (a, b) = (first_walk.next(), second_walk.next());
+        if a.is_none() && b.is_none() {
+            break;
+        }
+        positions.0.extend(a.map(|step| step.unwrap().position));
+        positions.1.extend(b.map(|step| step.unwrap().position));
+    }
+    assert_eq!(positions.0, expected);
+    assert_eq!(positions.1, expected);
+}
+
+#[test]
+fn snapshot_data() {
+    let mut machine = login_machine();
+    assert!(machine.reset().is_ok());
+    assert!(machine
+        .set_data("login", "b = true; i = 3; f = 2.5; s = \"text\"; f2 = 1.0")
+        .is_ok());
+    let json = serde_json::to_string(&machine.snapshot()).expect("Expected a json snapshot");
+
+    let mut restored = login_machine();
+    assert!(restored
+        .restore(serde_json::from_str(&json).expect("Expected a snapshot"))
+        .is_ok());
+    let data = restored.get_data("login").unwrap();
+    assert_eq!(json!(data), json!(machine.get_data("login").unwrap()));
+
+    // The types of the values survive
+    let f2 = data.iter().find(|d| d.name() == "f2").unwrap();
+    assert!(f2.value().is_float());
+    let i = data.iter().find(|d| d.name() == "i").unwrap();
+    assert!(i.value().is_int());
+}
+
+#[test]
+fn snapshot_restore_other_models() {
+    let mut machine = login_machine();
+    assert!(machine.reset().is_ok());
+    assert!(machine.step().is_ok());
+    let snapshot = machine.snapshot();
+
+    let mut other = Machine::new();
+    assert!(other
+        .load_models(
+            io::json_read::read(resource_path("petclinic.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+    let res = other.restore(snapshot);
+    assert!(res
+        .unwrap_err()
+        .contains("are not the models of the machine"));
+    assert_eq!(other.status, MachineStatus::NotStarted);
+}
+
+fn position(model_id: &str, element_id: &str) -> Position {
+    Position {
+        model_id: model_id.to_string(),
+        element_id: element_id.to_string(),
+    }
+}
+
+fn multi_model_machine() -> Machine {
+    let mut machine = Machine::new();
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("simpleMultiModel.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+    machine
+}
+
+#[test]
+fn snapshot_start_override() {
+    let mut machine = multi_model_machine();
+    assert!(machine.set_start(position("m1", "v1")).is_ok());
+    assert!(machine.reset().is_ok());
+    assert!(machine.step().is_ok());
+    let json = serde_json::to_string(&machine.snapshot()).expect("Expected a json snapshot");
+
+    // The restored machine keeps starting where it was told to, not at the start element
+    let mut restored = multi_model_machine();
+    assert!(restored
+        .restore(serde_json::from_str(&json).expect("Expected a snapshot"))
+        .is_ok());
+    assert!(restored.reset().is_ok());
+    assert_eq!(restored.current_position(), &position("m1", "v1"));
+
+    // Snapshots without the start override start at the start element of the models
+    let mut snapshot: serde_json::Value = serde_json::from_str(&json).unwrap();
+    snapshot.as_object_mut().unwrap().remove("start_override");
+    let mut restored = multi_model_machine();
+    assert!(restored
+        .restore(serde_json::from_value(snapshot).expect("Expected a snapshot"))
+        .is_ok());
+    assert!(restored.reset().is_ok());
+    assert_eq!(restored.current_position(), &position("m2", "v1"));
+}
+
+#[test]
+fn shared_state_jump() {
+    let mut machine = multi_model_machine();
+    assert!(machine.set_jump_probability(1f32).is_ok());
+
+    // m2.v2 and m1.v3 share a state, and are always jumped between
+    let steps: Vec<_> = machine.steps().take(5).map(|step| step.unwrap()).collect();
+    let positions: Vec<Position> = steps.iter().map(|s| s.position.clone()).collect();
+    assert_eq!(
+        positions,
+        vec![
+            position("m2", "v1"),
+            position("m2", "e2"),
+            position("m2", "v2"),
+            position("m1", "v3"),
+            position("m2", "v2"),
+        ]
+    );
+    assert_eq!(steps[2].jump, None);
+    assert_eq!(
+        steps[3].jump,
+        Some(Jump {
+            shared_state: "A SHARED STATE".to_string(),
+            from: position("m2", "v2"),
+        })
+    );
+    assert_eq!(machine.profile.jumps().count(), 2);
+
+    // Both sides of the jumps are visited
+    let statistics = machine.statistics();
+    assert_eq!(statistics.shared_state_jumps, 2);
+    assert_eq!(statistics.models["m1"].visits["v3"], 1);
+    assert_eq!(statistics.models["m2"].visits["v2"], 2);
+    assert_eq!(statistics.models["m1"].jumps_in, 1);
+    assert_eq!(statistics.models["m1"].jumps_out, 1);
+    assert_eq!(statistics.models["m2"].jumps_in, 1);
+    assert_eq!(statistics.models["m2"].jumps_out, 1);
+}
+
+#[test]
+fn shared_state_no_jump() {
+    let mut machine = multi_model_machine();
+    assert!(machine.set_jump_probability(0f32).is_ok());
+    assert!(machine.reset().is_ok());
+    while !machine.statistics().models["m2"].fullfilled {
+        let step = machine.step().unwrap();
+        assert_eq!(step.position.model_id, "m2");
+        assert_eq!(step.jump, None);
+    }
+
+    // A fullfilled model is left through the shared state anyway
+    let step = (0..6)
+        .map(|_| machine.step().unwrap())
+        .find(|step| step.jump.is_some())
+        .expect("Expected a jump");
+    assert_eq!(step.position, position("m1", "v3"));
+
+    // Negative tests
+    assert!(machine.set_jump_probability(1.5).is_err());
+    assert!(machine.set_jump_probability(-0.1).is_err());
+    assert!(machine.set_jump_probability(f32::NAN).is_err());
+}
+
+/*
+ * The models use different id schemes, and the same id for different kinds of elements.
+ */
+#[test]
+fn shared_state_different_ids() {
+    let mut first = graph::Model::new();
+    first.id = Some("first".to_string());
+    first.generator = Some("random(length(200))".to_string());
+    // The id 1 is an edge in the second model
+    first.start_element_id = Some("first:1".to_string());
+    for id in ["1", "2"] {
+        let mut vertex = graph::Vertex::new().id(id.to_string());
+        vertex.shared_state = (id == "2").then(|| "SHARED".to_string());
+        first.vertices.insert(id.to_string(), vertex);
+    }
+    for (id, source, target) in [("x", "1", "2"), ("y", "2", "1")] {
+        first.edges.insert(
+            id.to_string(),
+            graph::Edge::new()
+                .id(id.to_string())
+                .source_vertex_id(source.to_string())
+                .target_vertex_id(target.to_string()),
+        );
+    }
+
+    let mut second = graph::Model::new();
+    second.id = Some("second".to_string());
+    second.generator = Some("random(length(0))".to_string());
+    let mut vertex = graph::Vertex::new().id("n1".to_string());
+    vertex.shared_state = Some("SHARED".to_string());
+    second.vertices.insert("n1".to_string(), vertex);
+    second
+        .vertices
+        .insert("n2".to_string(), graph::Vertex::new().id("n2".to_string()));
+    for (id, source, target) in [("1", "n1", "n2"), ("2", "n2", "n1")] {
+        second.edges.insert(
+            id.to_string(),
+            graph::Edge::new()
+                .id(id.to_string())
+                .source_vertex_id(source.to_string())
+                .target_vertex_id(target.to_string()),
+        );
+    }
+
+    let mut machine = Machine::new();
+    machine.seed(42);
+    let models = graph::Models {
+        models: BTreeMap::from([("first".to_string(), first), ("second".to_string(), second)]),
+    };
+    assert!(machine.load_models(models).is_ok());
+    assert!(machine.set_jump_probability(0.5).is_ok());
+    assert!(machine.walk_with(|_| Ok(())).is_ok());
+    assert_eq!(machine.status, MachineStatus::Ended);
+
+    let jumps: Vec<&Jump> = machine
+        .profile
+        .jumps()
+        .map(|step| step.jump.as_ref().unwrap())
+        .collect();
+    assert!(!jumps.is_empty());
+    for jump in jumps {
+        assert!(jump.from == position("first", "2") || jump.from == position("second", "n1"));
+    }
+
+    // A replay records the same jumps
+    let path: Vec<Position> = machine
+        .profile
+        .steps
+        .iter()
+        .map(|s| s.position.clone())
+        .collect();
+    let mut replay = machine.clone();
+    replay.profile = Default::default();
+    assert!(replay.replay(path, |_, _| Ok(())).is_ok());
+    assert_eq!(
+        replay.profile.jumps().count(),
+        machine.profile.jumps().count()
+    );
+}
+
+#[test]
+fn start_in_several_models() {
+    let mut models = io::json_read::read(resource_path("simpleMultiModel.json").to_str().unwrap())
+        .expect("Expexted the test file to be loaded");
+    for model in models.models.values_mut() {
+        model.start_element_id = Some("v1".to_string());
+    }
+    let mut machine = Machine::new();
+    assert!(machine.load_models(models).is_ok());
+
+    // Both models have the start element v1
+    let err = machine.reset().unwrap_err();
+    assert!(err.contains("exists in more than one model"), "{}", err);
+    assert!(err.contains("m1:v1, m2:v1"), "{}", err);
+
+    assert_eq!(machine.find_position("m1:v1"), Ok(position("m1", "v1")));
+    assert_eq!(machine.find_position("m2:v2"), Ok(position("m2", "v2")));
+    assert!(machine.find_position("v1").is_err());
+    assert!(machine.find_position("m3:v1").is_err());
+
+    assert!(machine.set_start(position("m1", "v1")).is_ok());
+    assert!(machine.reset().is_ok());
+    assert_eq!(machine.current_position(), &position("m1", "v1"));
+}
+
+#[test]
+fn unreachable_model() {
+    let mut models = io::read_all(&[
+        (
+            resource_path("login.json").to_str().unwrap().to_string(),
+            None,
+        ),
+        (
+            resource_path("unnamed.json").to_str().unwrap().to_string(),
+            None,
+        ),
+    ])
+    .expect("Expected models");
+
+    // The unnamed model shares no state with the login model
+    let mut machine = Machine::new();
+    assert!(machine.load_models(models.clone()).is_ok());
+    assert_eq!(
+        machine.reset(),
+        Err("The model id: unnamed can not be reached from the start element through shared states, and would never fullfill its stop conditions".to_string())
+    );
+
+    // ...which is fine, as long as it has nothing to do
+    if let Some(model) = models.models.get_mut("unnamed") {
+        model.generator = Some("random(length(0))".to_string());
+    }
+    let mut machine = Machine::new();
+    assert!(machine.load_models(models).is_ok());
+    assert!(machine.walk_with(|_| Ok(())).is_ok());
+    assert_eq!(machine.status, MachineStatus::Ended);
+}
+
+#[test]
+fn dependency_out_of_range() {
+    let mut models = io::json_read::read(resource_path("login.json").to_str().unwrap())
+        .expect("Expected models");
+    if let Some(edge) = models
+        .models
+        .get_mut("login")
+        .and_then(|model| model.edges.get_mut("e1"))
+    {
+        edge.dependency = Some(101);
+    }
+
+    let mut machine = Machine::new();
+    assert_eq!(
+        machine.load_models(models),
+        Err(
+            "The dependency of edge e1 in model id: login has to be between 0 and 100, got: 101"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn fullfilled_models_independently() {
+    let mut machine = Machine::new();
+    machine.seed(1);
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("petclinic.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+    assert!(machine.walk_with(|_| Ok(())).is_ok());
+
+    let statistics = machine.statistics();
+    assert_eq!(statistics.status, MachineStatus::Ended);
+    for model in statistics.models.values() {
+        assert!(model.fullfilled, "{} is not fullfilled", model.model_name);
+    }
+}
+
+/*
+ * Both models use the same element ids, and their actions work on a variable with the same name.
+ */
+#[test]
+fn actions_of_duplicate_ids() {
+    let mut machine = Machine::new();
+    machine.seed(1);
+    assert!(machine
+        .load_models(
+            io::json_read::read(resource_path("multiModelActions.json").to_str().unwrap())
+                .expect("Expexted the test file to be loaded")
+        )
+        .is_ok());
+
+    let count = |machine: &Machine, model_id: &str| {
+        let data = machine.get_data(model_id).unwrap();
+        let count = data.iter().find(|d| d.name() == "count").unwrap();
+        count.value().as_int().unwrap()
+    };
+
+    // The model actions have run once for each model
+    assert!(machine.reset().is_ok());
+    assert_eq!(machine.current_position(), &position("m1", "v1"));
+    assert_eq!(count(&machine, "m1"), 0);
+    assert_eq!(count(&machine, "m2"), 100);
+
+    let mut expected = BTreeMap::from([("m1", 0), ("m2", 100)]);
+    while machine.status == MachineStatus::Running && !machine.is_all_fullfilled() {
+        let step = machine.step().unwrap();
+        if step.position.element_id == "e1" {
+            let model_id = step.position.model_id.as_str();
+            *expected.get_mut(model_id).unwrap() += if model_id == "m1" { 1 } else { 10 };
+        }
+        assert_eq!(count(&machine, "m1"), expected["m1"]);
+        assert_eq!(count(&machine, "m2"), expected["m2"]);
+    }
+    assert!(expected["m1"] > 0);
+    assert!(expected["m2"] > 100);
+}
+
+#[test]
+fn global_data() {
+    let global_machine = || {
+        let mut machine = Machine::new();
+        machine.seed(1);
+        assert!(machine
+            .load_models(
+                io::json_read::read(resource_path("globalData.json").to_str().unwrap())
+                    .expect("Expexted the test file to be loaded")
+            )
+            .is_ok());
+        machine
+    };
+    let logged_in = |data: &[machine::Data]| {
+        let data = data.iter().find(|d| d.name() == "loggedIn").unwrap();
+        data.value().as_boolean().unwrap()
+    };
+
+    let mut machine = global_machine();
+    assert!(machine.reset().is_ok());
+    assert!(!logged_in(&machine.get_global_data()));
+    assert!(machine.get_data("login").unwrap().is_empty());
+
+    // The guard in the account model reads the global variable set by the login model
+    let mut settings = 0;
+    while machine.status == MachineStatus::Running && !machine.is_all_fullfilled() {
+        let step = machine.step().unwrap();
+        assert!(step.data.iter().all(|d| !d.name().starts_with("global")));
+        if step.position.model_id == "account" {
+            assert!(!logged_in(&step.data));
+            assert!(logged_in(&step.global_data));
+        }
+        if step.position.element_id == "ae1" {
+            settings += 1;
+        }
+    }
+    assert!(settings > 0);
+
+    assert!(machine.set_data("account", "global.user = \"bob\"").is_ok());
+    let json = serde_json::to_string(&machine.snapshot()).expect("Expected a json snapshot");
+    let mut restored = global_machine();
+    assert!(restored
+        .restore(serde_json::from_str(&json).expect("Expected a snapshot"))
+        .is_ok());
+    assert_eq!(
+        json!(restored.get_global_data()),
+        json!(machine.get_global_data())
+    );
+    assert_eq!(restored.get_global_data().len(), 2);
+}
@@ -1,4 +1,5 @@
-use machine::MachineStatus;
+use machine::{snapshot::Snapshot, MachineStatus};
+use percent_encoding::percent_decode_str;
 use std::convert::Infallible;
 use warp::{self, http::StatusCode};
 
@@ -32,3 +33,119 @@ pub async fn get_next(machine: MachineRt) -> Result<Box<dyn warp::Reply>, Infall
         }
     }
 }
+
+pub async fn get_statistics(machine: MachineRt) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let m = machine.lock().await;
+
+    let statistics = m.statistics();
+    log::debug!("getStatistics: {:?}", statistics);
+    Ok(Box::new(warp::reply::json(&statistics)))
+}
+
+pub async fn get_data(machine: MachineRt) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let m = machine.lock().await;
+
+    let model_id = m.current_position().model_id.clone();
+    match m.get_data(&model_id) {
+        Ok(data) => {
+            let global = m.get_global_data();
+            log::debug!("getData: {:?}, global: {:?}", data, global);
+            Ok(Box::new(warp::reply::json(&serde_json::json!({
+                "data": data,
+                "global": global,
+            }))))
+        }
+
+        Err(err) => {
+            log::error!("getData: {:?}", err);
+            Ok(Box::new(StatusCode::BAD_REQUEST))
+        }
+    }
+}
+
+pub async fn get_global_data(machine: MachineRt) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let m = machine.lock().await;
+
+    let data = m.get_global_data();
+    log::debug!("getGlobalData: {:?}", data);
+    Ok(Box::new(warp::reply::json(&data)))
+}
+
+pub async fn set_data(
+    script: String,
+    machine: MachineRt,
+) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let mut m = machine.lock().await;
+
+    let script = match percent_decode_str(&script).decode_utf8() {
+        Ok(script) => script.to_string(),
+        Err(err) => {
+            log::error!("setData: {:?}", err);
+            return Ok(Box::new(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    let model_id = m.current_position().model_id.clone();
+    match m.set_data(&model_id, &script) {
+        Ok(()) => {
+            log::debug!("setData: {:?}", script);
+            Ok(Box::new(StatusCode::OK))
+        }
+
+        Err(err) => {
+            log::error!("setData: {:?}", err);
+            Ok(Box::new(StatusCode::BAD_REQUEST))
+        }
+    }
+}
+
+pub async fn fail(reason: String, machine: MachineRt) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let mut m = machine.lock().await;
+
+    let reason = match percent_decode_str(&reason).decode_utf8() {
+        Ok(reason) => reason.to_string(),
+        Err(err) => {
+            log::error!("fail: {:?}", err);
+            return Ok(Box::new(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    match m.fail(&reason) {
+        Ok(()) => {
+            log::debug!("fail: {:?}", reason);
+            Ok(Box::new(StatusCode::OK))
+        }
+
+        Err(err) => {
+            log::error!("fail: {:?}", err);
+            Ok(Box::new(StatusCode::BAD_REQUEST))
+        }
+    }
+}
+
+pub async fn get_snapshot(machine: MachineRt) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let m = machine.lock().await;
+
+    let snapshot = m.snapshot();
+    log::debug!("getSnapshot: {:?}", snapshot.current_position);
+    Ok(Box::new(warp::reply::json(&snapshot)))
+}
+
+pub async fn restore(
+    snapshot: Snapshot,
+    machine: MachineRt,
+) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let mut m = machine.lock().await;
+
+    match m.restore(snapshot) {
+        Ok(()) => {
+            log::debug!("restore: {:?}", m.current_position());
+            Ok(Box::new(StatusCode::OK))
+        }
+
+        Err(err) => {
+            log::error!("restore: {:?}", err);
+            Ok(Box::new(StatusCode::BAD_REQUEST))
+        }
+    }
+}
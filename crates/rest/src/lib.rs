@@ -16,12 +16,25 @@ pub fn init_machine(machine: Machine) -> MachineRt {
     Arc::new(Mutex::new(machine))
 }
 
+/*
+ * Serves the machine until the process is interrupted (ctrl-c), then returns the machine
+ * so that the caller can report on the run.
+ */
 #[tokio::main]
-pub async fn run_rest_service(machine: Machine) {
+pub async fn run_rest_service(machine: Machine) -> Machine {
     let m = init_machine(machine);
-    let graphwalker_routes = routes::graphwalker_routes(m);
+    let graphwalker_routes = routes::graphwalker_routes(m.clone());
 
-    warp::serve(graphwalker_routes)
-        .run(([127, 0, 0, 1], 3000))
-        .await;
+    let (_addr, server) = warp::serve(graphwalker_routes).bind_with_graceful_shutdown(
+        ([127, 0, 0, 1], 3000),
+        async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("Failed to listen for ctrl-c");
+        },
+    );
+    server.await;
+
+    let machine = m.lock().await;
+    machine.clone()
 }
@@ -7,7 +7,15 @@ use crate::MachineRt;
 pub fn graphwalker_routes(
     machine: MachineRt,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    has_next(machine.clone()).or(get_next(machine))
+    has_next(machine.clone())
+        .or(get_next(machine.clone()))
+        .or(get_statistics(machine.clone()))
+        .or(get_data(machine.clone()))
+        .or(get_global_data(machine.clone()))
+        .or(set_data(machine.clone()))
+        .or(fail(machine.clone()))
+        .or(get_snapshot(machine.clone()))
+        .or(restore(machine))
 }
 
 fn has_next(
@@ -28,6 +36,70 @@ fn get_next(
         .and_then(handlers::get_next)
 }
 
+fn get_statistics(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("getStatistics")
+        .and(warp::get())
+        .and(with_machine(machine))
+        .and_then(handlers::get_statistics)
+}
+
+fn get_data(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("getData")
+        .and(warp::get())
+        .and(with_machine(machine))
+        .and_then(handlers::get_data)
+}
+
+fn get_global_data(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("getGlobalData")
+        .and(warp::get())
+        .and(with_machine(machine))
+        .and_then(handlers::get_global_data)
+}
+
+fn set_data(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("setData" / String)
+        .and(warp::put())
+        .and(with_machine(machine))
+        .and_then(handlers::set_data)
+}
+
+fn fail(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("fail" / String)
+        .and(warp::put())
+        .and(with_machine(machine))
+        .and_then(handlers::fail)
+}
+
+fn get_snapshot(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("getSnapshot")
+        .and(warp::get())
+        .and(with_machine(machine))
+        .and_then(handlers::get_snapshot)
+}
+
+fn restore(
+    machine: MachineRt,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("restore")
+        .and(warp::put())
+        .and(warp::body::json())
+        .and(with_machine(machine))
+        .and_then(handlers::restore)
+}
+
 fn with_machine(
     machine: MachineRt,
 ) -> impl Filter<Extract = (MachineRt,), Error = Infallible> + Clone {
@@ -1,5 +1,5 @@
 use assert_json_diff::assert_json_eq;
-use machine::{Machine, Position};
+use machine::{Machine, MachineStatus, Position};
 use rest::{init_machine, routes};
 
 fn resource_path(resource: &str) -> std::path::PathBuf {
@@ -110,3 +110,283 @@ async fn login_model() {
     assert_eq!(res.status(), 200, "Should return 200 OK.");
     assert_eq!(res.body(), "false", "Should return false.");
 }
+
+#[tokio::test]
+async fn get_and_set_data() {
+    let models = io::read(
+        resource_path("login.json")
+            .to_str()
+            .expect("The login.json file to be readable"),
+    )
+    .expect("The login.json file to be loaded");
+
+    let mut machine = machine::Machine::new();
+    assert!(machine.load_models(models).is_ok());
+    assert!(machine.reset().is_ok());
+
+    let m = init_machine(machine);
+    let graphwalker_routes = routes::graphwalker_routes(m);
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getData")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+    let body = std::str::from_utf8(res.body()).expect("Found invalid UTF-8");
+    assert_json_eq!(
+        serde_json::from_str::<serde_json::Value>(body).unwrap(),
+        serde_json::json!({
+            "data": [
+                {"name": "rememberMe", "value": false},
+                {"name": "validLogin", "value": false}
+            ],
+            "global": []
+        })
+    );
+
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/setData/validLogin=true;rememberMe=true;")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getData")
+        .reply(&graphwalker_routes)
+        .await;
+    let body = std::str::from_utf8(res.body()).expect("Found invalid UTF-8");
+    assert_json_eq!(
+        serde_json::from_str::<serde_json::Value>(body).unwrap(),
+        serde_json::json!({
+            "data": [
+                {"name": "rememberMe", "value": true},
+                {"name": "validLogin", "value": true}
+            ],
+            "global": []
+        })
+    );
+
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/setData/validLogin%20=%20!")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 400, "Should return 400 Bad Request.");
+}
+
+#[tokio::test]
+async fn get_global_data() {
+    let models = io::read(
+        resource_path("login.json")
+            .to_str()
+            .expect("The login.json file to be readable"),
+    )
+    .expect("The login.json file to be loaded");
+
+    let mut machine = machine::Machine::new();
+    assert!(machine.load_models(models).is_ok());
+    assert!(machine.reset().is_ok());
+
+    let m = init_machine(machine);
+    let graphwalker_routes = routes::graphwalker_routes(m);
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getGlobalData")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+    assert_eq!(res.body(), "[]", "Should return no global data.");
+
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/setData/global.user=%22bob%22;validLogin=true;")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getGlobalData")
+        .reply(&graphwalker_routes)
+        .await;
+    let body = std::str::from_utf8(res.body()).expect("Found invalid UTF-8");
+    assert_json_eq!(
+        serde_json::from_str::<serde_json::Value>(body).unwrap(),
+        serde_json::json!([{"name": "user", "value": "bob"}])
+    );
+
+    // The global variables are returned in a section of their own
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getData")
+        .reply(&graphwalker_routes)
+        .await;
+    let body = std::str::from_utf8(res.body()).expect("Found invalid UTF-8");
+    assert_json_eq!(
+        serde_json::from_str::<serde_json::Value>(body).unwrap(),
+        serde_json::json!({
+            "data": [
+                {"name": "rememberMe", "value": false},
+                {"name": "validLogin", "value": true}
+            ],
+            "global": [{"name": "user", "value": "bob"}]
+        })
+    );
+}
+
+#[tokio::test]
+async fn fail_step() {
+    let models = io::read(
+        resource_path("login.json")
+            .to_str()
+            .expect("The login.json file to be readable"),
+    )
+    .expect("The login.json file to be loaded");
+
+    let mut machine = machine::Machine::new();
+    assert!(machine.load_models(models).is_ok());
+    assert!(machine.reset().is_ok());
+
+    let m = init_machine(machine);
+    let graphwalker_routes = routes::graphwalker_routes(m.clone());
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getNext")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/fail/Login%20window%20did%20not%20show")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/hasNext")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+    assert_eq!(res.body(), "false", "Should return false.");
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getNext")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 400, "Should return 400 Bad Request.");
+
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/fail/again")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 400, "Should return 400 Bad Request.");
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getStatistics")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+    let statistics: serde_json::Value =
+        serde_json::from_slice(res.body()).expect("Expected statistics as json");
+    assert_eq!(statistics["status"], "Failed");
+    assert_eq!(statistics["total_steps"], 1);
+    assert_eq!(statistics["failure"]["reason"], "Login window did not show");
+
+    let machine = m.lock().await;
+    assert_eq!(machine.status, MachineStatus::Failed);
+    let failure = machine.profile.failure.clone().expect("Expected a failure");
+    assert_eq!(failure.reason, "Login window did not show");
+    assert_eq!(
+        failure.step.expect("Expected a failed step").position,
+        Position {
+            model_id: "login".to_string(),
+            element_id: "n1".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn snapshot_restore() {
+    let login_machine = || {
+        let models = io::read(
+            resource_path("login.json")
+                .to_str()
+                .expect("The login.json file to be readable"),
+        )
+        .expect("The login.json file to be loaded");
+        let mut machine = machine::Machine::new();
+        assert!(machine.load_models(models).is_ok());
+        machine
+    };
+
+    let mut machine = login_machine();
+    machine.seed(8739438725484);
+    assert!(machine.reset().is_ok());
+    let graphwalker_routes = routes::graphwalker_routes(init_machine(machine));
+
+    for _ in 0..10 {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/getNext")
+            .reply(&graphwalker_routes)
+            .await;
+        assert_eq!(res.status(), 200, "Should return 200 OK.");
+    }
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/getSnapshot")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+    let snapshot = res.body().clone();
+
+    let mut expected = Vec::new();
+    for _ in 0..10 {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/getNext")
+            .reply(&graphwalker_routes)
+            .await;
+        let step: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        expected.push(step["position"].clone());
+    }
+
+    // A new server, as after a restart, continues the walk from the snapshot
+    let graphwalker_routes = routes::graphwalker_routes(init_machine(login_machine()));
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/restore")
+        .body(snapshot)
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 200, "Should return 200 OK.");
+
+    for position in expected {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/getNext")
+            .reply(&graphwalker_routes)
+            .await;
+        let step: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(step["position"], position);
+    }
+
+    let res = warp::test::request()
+        .method("PUT")
+        .path("/restore")
+        .body("{}")
+        .reply(&graphwalker_routes)
+        .await;
+    assert_eq!(res.status(), 400, "Should return 400 Bad Request.");
+}